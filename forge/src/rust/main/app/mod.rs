@@ -16,9 +16,15 @@ use super::navigation::RouteDef;
 use ui_components::layout::main_menu::MainMenu;
 use ui_components::layout::root::Root;
 use ui_components::menu::Menu;
+use ui_components::menu::MenuHeader;
 use ui_components::widgets::logo::Logo;
 
 use super::views::content::Content;
+use super::views::test_dashboard::TEST_DASHBOARD_SEGMENT_1;
+use super::views::test_dashboard::TEST_DASHBOARD_SEGMENT_2;
+use super::views::test_dashboard::TestDashboard;
+use super::views::test_dashboard::TestDashboardMenuItem;
+use super::views::test_dashboard::TestRunState;
 
 /// Main application component
 #[component]
@@ -30,6 +36,7 @@ pub fn App(
     logo: Option<&'static str>,
 ) -> impl IntoView {
     let _store = Store::new(State::new());
+    let test_run = TestRunState::new();
 
     let menu_defs = {
         let routes = routes.clone();
@@ -40,21 +47,38 @@ pub fn App(
             let location = window.location();
             let path = location.pathname().expect("We are running csr mode. Window should exist, location should exist and pathname should be there");
 
-            StaticVec::from(
-                routes
-                    .iter()
-                    .flat_map(move |route| route.as_menu_items(PathSpec::Root, &path, menu))
-                    .collect::<Vec<_>>(),
-            )
+            let mut items = routes
+                .iter()
+                .flat_map(|route| route.as_menu_items(PathSpec::Root, &path, menu))
+                .collect::<Vec<_>>();
+
+            items.push(view! { <MenuHeader label="TESTS" class="" /> }.into_any());
+            items.push(
+                view! { <TestDashboardMenuItem location=&path store=menu state=test_run /> }
+                    .into_any(),
+            );
+
+            StaticVec::from(items)
         }
     };
 
     let route_defs = {
         move || {
+            let dashboard = {
+                let routes = routes.clone();
+                PathSpec::Level2(TEST_DASHBOARD_SEGMENT_1, TEST_DASHBOARD_SEGMENT_2).as_route(
+                    move || {
+                        let routes = routes.clone();
+                        view! { <TestDashboard routes state=test_run /> }.into_any()
+                    },
+                )
+            };
+
             StaticVec::from(
                 routes
                     .iter()
                     .flat_map(|route| route.as_routes(PathSpec::Root).into_iter())
+                    .chain(std::iter::once(dashboard))
                     .collect::<Vec<_>>(),
             )
         }
@@ -16,8 +16,10 @@
 
 pub mod app;
 pub mod navigation;
+pub mod runner;
 pub mod section;
 pub mod story;
+pub mod testing;
 pub mod views;
 
 pub use app::*;
@@ -16,6 +16,8 @@ use ui_components::primitives::markdown::Markdown;
 use utils::prelude::ThreadSafe;
 
 use crate::IntoStory;
+use crate::runner::StorySuite;
+use crate::runner::TestSuite;
 use crate::views::story::EmbeddedStory;
 
 use super::Section;
@@ -302,6 +304,10 @@ impl PathSpec {
 }
 
 /// Define routes in the application
+///
+/// Prefer creating the routes with [RouteDef::story], [RouteDef::private],
+/// [RouteDef::section] and [RouteDef::header], new fields of the variants are
+/// filled in by them, see the `CHANGES.md`.
 #[derive(Debug, Clone)]
 pub enum RouteDef {
     /// Menu entry which can be navigated
@@ -330,29 +336,36 @@ pub enum RouteDef {
         ///   Current version of the Markdown parser doesn't allow to set this
         ///   value.
         ///
+        /// - **tests** - wherever we should show the plays of the story under the canvas
+        ///
         /// # Embedding in the section
         ///
         /// Inside the Markdown returned from [Section::description] method
         /// you can add the `<Story />` tag. It has the following boolean attributes
         ///
         /// - **controls**
-        ///
+        /// - **tests**
         ///
         /// The code below will enable all of the before mentioned attributes
         ///
         /// ```markdown
         ///
-        /// <Story of="path/to/the/substory" controls />
+        /// <Story of="path/to/the/substory" controls tests />
         ///
         /// ```
         ///
         ///
-        embedded: fn(view: bool, controls: bool, description: bool) -> AnyView,
+        embedded: fn(view: bool, controls: bool, description: bool, tests: bool) -> AnyView,
         /// Wherever this route should be hidden from the menu/router but still
         /// provide to the story embedding resolution
         ///
         /// hidden entry hides it's all children
         private: bool,
+        /// Returns the tests of the route
+        ///
+        /// Routes which don't have any tests, like sections, return `None`.
+        /// It's used by the [runner][crate::runner] to run the plays without the UI.
+        suite: fn() -> Option<Box<dyn TestSuite>>,
     },
     /// Grouping for a set of routes without any path to be taken
     Header {
@@ -524,14 +537,15 @@ impl RouteDef {
             path,
             label,
             component: || view! { <Story<S> /> }.into_any(),
-            embedded: |view, controls, description| {
+            embedded: |view, controls, description, tests| {
                 view! {
-                    <EmbeddedStory<S> view  controls description />
+                    <EmbeddedStory<S> view controls description tests />
                 }
                 .into_any()
             },
             subroutes: S::default().into_story().subroutes(),
             private: false,
+            suite: || Some(Box::new(StorySuite::<S>::new())),
         }
     }
 
@@ -554,14 +568,15 @@ impl RouteDef {
             path,
             label,
             component: || view! { <Story<S> /> }.into_any(),
-            embedded: |view, controls, description| {
+            embedded: |view, controls, description, tests| {
                 view! {
-                    <EmbeddedStory<S> view  controls description />
+                    <EmbeddedStory<S> view controls description tests />
                 }
                 .into_any()
             },
             subroutes: S::default().into_story().subroutes(),
             private: true,
+            suite: || Some(Box::new(StorySuite::<S>::new())),
         }
     }
 
@@ -586,11 +601,12 @@ impl RouteDef {
             path,
             label,
             component: || view! { <section::Section<S> /> }.into_any(),
-            embedded: |_, _, _| {
+            embedded: |_, _, _, _| {
                 view! { <Markdown src="> Embedding sections is not allowed"  /> }.into_any()
             },
             subroutes: S::default().subroutes(),
             private: false,
+            suite: || None,
         }
     }
 
@@ -0,0 +1,433 @@
+//! Capturing of the console output and the panics of the step
+//!
+//! While the step is running, `console.log`, `console.info`, `console.warn` and
+//! `console.error` are replaced with the `wasm_bindgen` closures which record the
+//! messages and pass them to the original ones. Nothing is evaluated from the
+//! strings, so the capture works under the Content Security Policy without the
+//! `unsafe-eval`.
+//!
+//! Panicking step never completes, so the panic hook records the panic, puts the
+//! original console methods back and hands the captured logs to the handler
+//! registered with [on_panic].
+//!
+//! # Limitations
+//!
+//! On `wasm32-unknown-unknown` the panic aborts. The hook still runs, but right
+//! after it the module traps and nothing unwinds: the task running the play is
+//! gone and the values borrowed at the moment of the panic stay borrowed. The
+//! handler is scheduled on the browser timeout as a best effort attempt to show
+//! the failure, but it isn't guaranteed to run correctly, and the test runner
+//! can't be used again until the page is reloaded.
+
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::panic;
+use std::panic::PanicHookInfo;
+use std::rc::Rc;
+use std::sync::Once;
+use std::time::Duration;
+
+use leptos::prelude::set_timeout;
+use leptos::wasm_bindgen::JsCast;
+use leptos::wasm_bindgen::JsValue;
+use leptos::wasm_bindgen::closure::Closure;
+use leptos::web_sys::js_sys::Array;
+use leptos::web_sys::js_sys::Function;
+use leptos::web_sys::js_sys::JSON;
+use leptos::web_sys::js_sys::Reflect;
+use leptos::web_sys::js_sys::global;
+
+/// Most arguments of the console method which are captured
+///
+/// Replacements are `wasm_bindgen` closures, which take a fixed number of
+/// arguments, so the arguments past this one are neither recorded nor passed to
+/// the original method. Arguments which are `undefined` at the end are left out.
+const MAX_ARGS: usize = 8;
+
+/// Replacement of the console method receiving its arguments
+type ConsoleFn = dyn Fn(JsValue, JsValue, JsValue, JsValue, JsValue, JsValue, JsValue, JsValue);
+
+/// Level of the captured message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LogLevel {
+    /// `console.log`
+    Log,
+    /// `console.info`
+    Info,
+    /// `console.warn`
+    Warn,
+    /// `console.error`
+    Error,
+    /// Rust panic
+    Panic,
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use LogLevel::*;
+        match self {
+            Log => f.write_str("log"),
+            Info => f.write_str("info"),
+            Warn => f.write_str("warn"),
+            Error => f.write_str("error"),
+            Panic => f.write_str("panic"),
+        }
+    }
+}
+
+/// Console methods which are captured
+const METHODS: [(&str, LogLevel); 4] = [
+    ("log", LogLevel::Log),
+    ("info", LogLevel::Info),
+    ("warn", LogLevel::Warn),
+    ("error", LogLevel::Error),
+];
+
+/// Single captured message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LogEntry {
+    /// Level of the message
+    pub(crate) level: LogLevel,
+    /// Text of the message
+    pub(crate) message: String,
+}
+
+/// Console output and panic captured while the step was running
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct StepLogs {
+    /// Messages in the order in which they were logged
+    pub(crate) entries: Vec<LogEntry>,
+}
+
+impl StepLogs {
+    /// Returns `true` if the step didn't log anything
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the message of the panic, if the step has panicked
+    pub(crate) fn panic(&self) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|entry| entry.level == LogLevel::Panic)
+            .map(|entry| entry.message.as_str())
+    }
+
+    /// Returns the logs formatted as the Markdown code block
+    pub(crate) fn to_markdown(&self) -> String {
+        let lines = self
+            .entries
+            .iter()
+            .map(|entry| format!("[{}] {}", entry.level, entry.message))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!("```text\n{lines}\n```")
+    }
+}
+
+/// Formats the value passed to the console method
+fn format_value(value: &JsValue) -> String {
+    if let Some(text) = value.as_string() {
+        return text;
+    }
+
+    JSON::stringify(value)
+        .ok()
+        .and_then(|json| json.as_string())
+        .unwrap_or_else(|| format!("{value:?}"))
+}
+
+/// Collects the arguments passed to the console method, without the missing ones at the end
+fn arguments(args: [JsValue; MAX_ARGS]) -> Vec<JsValue> {
+    let count = args
+        .iter()
+        .rposition(|arg| !arg.is_undefined())
+        .map_or(0, |last| last + 1);
+
+    args.into_iter().take(count).collect()
+}
+
+/// Formats the arguments of the console method the same way as the browser does
+fn format_args(args: &Array) -> String {
+    args.iter()
+        .map(|value| format_value(&value))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Console methods replaced while at least one capture is running
+///
+/// Captures can overlap, for example when the dashboard runs all plays while the
+/// test viewer plays a story. They share the same replacements, which record the
+/// messages in every running capture, so the captures can finish in any order.
+struct Wrappers {
+    /// The `console` object
+    console: JsValue,
+    /// Console methods replaced by the wrappers
+    originals: Vec<(&'static str, JsValue)>,
+    /// Replacements recording the messages, which must live as long as they are installed
+    _callbacks: Vec<Closure<ConsoleFn>>,
+}
+
+impl Wrappers {
+    /// Replaces the console methods with the ones recording the messages
+    fn install() -> Self {
+        let console =
+            Reflect::get(&global(), &JsValue::from_str("console")).unwrap_or(JsValue::UNDEFINED);
+        let mut wrappers = Self {
+            console: console.clone(),
+            originals: Vec::new(),
+            _callbacks: Vec::new(),
+        };
+
+        if !console.is_object() {
+            return wrappers;
+        }
+
+        for (method, level) in METHODS {
+            let Ok(original) = Reflect::get(&console, &JsValue::from_str(method)) else {
+                continue;
+            };
+            let Some(original_fn) = original.dyn_ref::<Function>().cloned() else {
+                continue;
+            };
+
+            let this = console.clone();
+            let callback = Closure::<ConsoleFn>::new(move |a0, a1, a2, a3, a4, a5, a6, a7| {
+                let args = arguments([a0, a1, a2, a3, a4, a5, a6, a7])
+                    .into_iter()
+                    .collect::<Array>();
+
+                record(level, &args);
+                // Failing original method can't fail the step, the message is already recorded
+                let _ = Reflect::apply(&original_fn, &this, &args);
+            });
+
+            if Reflect::set(&console, &JsValue::from_str(method), callback.as_ref()).is_ok() {
+                wrappers.originals.push((method, original));
+                wrappers._callbacks.push(callback);
+            }
+        }
+
+        wrappers
+    }
+
+    /// Puts the original console methods back
+    ///
+    /// Calling it more than once does nothing. The callbacks are kept until the
+    /// wrappers are dropped, because the panic can happen inside of one of them.
+    fn restore(&mut self) {
+        for (method, original) in self.originals.drain(..) {
+            // If we can't put the method back, there is nothing else we can do about it
+            let _ = Reflect::set(&self.console, &JsValue::from_str(method), &original);
+        }
+    }
+}
+
+impl Drop for Wrappers {
+    fn drop(&mut self) {
+        self.restore();
+    }
+}
+
+/// Records the message in every running capture
+fn record(level: LogLevel, args: &Array) {
+    let message = format_args(args);
+
+    CAPTURES.with(|captures| {
+        let Ok(captures) = captures.try_borrow() else {
+            return;
+        };
+
+        for logs in captures.iter() {
+            if let Ok(mut logs) = logs.try_borrow_mut() {
+                logs.entries.push(LogEntry {
+                    level,
+                    message: message.clone(),
+                });
+            }
+        }
+    });
+}
+
+thread_local! {
+    /// Logs of the captures which are running right now, the last started last
+    static CAPTURES: RefCell<Vec<Rc<RefCell<StepLogs>>>> = const { RefCell::new(Vec::new()) };
+    /// Replaced console methods, installed while any capture is running
+    static WRAPPERS: RefCell<Option<Wrappers>> = const { RefCell::new(None) };
+    /// Handler of the panic in the running step
+    static PANIC_HANDLER: RefCell<Option<Rc<dyn Fn(StepLogs)>>> = const { RefCell::new(None) };
+}
+
+/// Captures the console output of the step until it's [finished][ConsoleCapture::finish]
+pub(crate) struct ConsoleCapture {
+    /// Messages captured by this capture
+    logs: Rc<RefCell<StepLogs>>,
+}
+
+impl ConsoleCapture {
+    /// Starts capturing the console output and the panics
+    ///
+    /// The console methods are replaced by the first running capture and put
+    /// back when the last one is dropped.
+    pub(crate) fn start() -> Self {
+        install_panic_hook();
+
+        let logs = Rc::new(RefCell::new(StepLogs::default()));
+        let first = CAPTURES.with(|captures| {
+            let mut captures = captures.borrow_mut();
+            captures.push(Rc::clone(&logs));
+            captures.len() == 1
+        });
+
+        if first {
+            let wrappers = Wrappers::install();
+            WRAPPERS.with(|current| current.replace(Some(wrappers)));
+        }
+
+        Self { logs }
+    }
+
+    /// Stops capturing and returns the captured messages
+    pub(crate) fn finish(self) -> StepLogs {
+        self.logs.borrow().clone()
+    }
+}
+
+impl Drop for ConsoleCapture {
+    fn drop(&mut self) {
+        let last = CAPTURES.with(|captures| {
+            let mut captures = captures.borrow_mut();
+            captures.retain(|logs| !Rc::ptr_eq(logs, &self.logs));
+            captures.is_empty()
+        });
+
+        if last {
+            let wrappers = WRAPPERS.with(|current| current.take());
+            drop(wrappers);
+        }
+    }
+}
+
+/// Keeps the panic handler registered with [on_panic]
+///
+/// Previous handler is restored when the guard is dropped.
+pub(crate) struct PanicGuard {
+    /// Handler which was registered before
+    previous: Option<Rc<dyn Fn(StepLogs)>>,
+}
+
+impl Drop for PanicGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        PANIC_HANDLER.with(|handler| handler.replace(previous));
+    }
+}
+
+/// Registers the handler called when the running step panics
+///
+/// Handler receives the logs captured by the step, including the panic. It's
+/// called at most once.
+pub(crate) fn on_panic(handler: impl Fn(StepLogs) + 'static) -> PanicGuard {
+    let handler: Rc<dyn Fn(StepLogs)> = Rc::new(handler);
+    let previous = PANIC_HANDLER.with(|current| current.replace(Some(handler)));
+
+    PanicGuard { previous }
+}
+
+/// Installs the panic hook which records the panics of the steps
+///
+/// The hook which was set before, like the `console_error_panic_hook`, is still called.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            record_panic(info);
+            previous(info);
+        }));
+    });
+}
+
+/// Records the panic in the active capture and schedules the panic handler
+///
+/// The console is restored before the previous hook is called, so the panic
+/// message shows up in the devtools only once.
+fn record_panic(info: &PanicHookInfo<'_>) {
+    // Panic can happen while the state is borrowed, so we never borrow it without checking
+    WRAPPERS.with(|wrappers| {
+        if let Ok(mut wrappers) = wrappers.try_borrow_mut()
+            && let Some(wrappers) = wrappers.as_mut()
+        {
+            wrappers.restore();
+        }
+    });
+
+    // The panic belongs to the capture started last
+    let capture = CAPTURES.with(|captures| {
+        captures
+            .try_borrow()
+            .ok()
+            .and_then(|captures| captures.last().cloned())
+    });
+    let Some(capture) = capture else {
+        return;
+    };
+
+    let logs = {
+        let Ok(mut logs) = capture.try_borrow_mut() else {
+            return;
+        };
+
+        logs.entries.push(LogEntry {
+            level: LogLevel::Panic,
+            message: info.to_string(),
+        });
+        logs.clone()
+    };
+
+    let handler = PANIC_HANDLER.with(|handler| {
+        handler
+            .try_borrow_mut()
+            .ok()
+            .and_then(|mut handler| handler.take())
+    });
+
+    if let Some(handler) = handler {
+        set_timeout(move || handler(logs), Duration::ZERO);
+    }
+}
+
+#[cfg(test)]
+/// Tests of the captured logs
+mod tests {
+    use super::LogEntry;
+    use super::LogLevel;
+    use super::StepLogs;
+
+    /// Captured messages are shown with their level and the panic can be found
+    #[test]
+    fn step_logs() {
+        let logs = StepLogs {
+            entries: vec![
+                LogEntry {
+                    level: LogLevel::Warn,
+                    message: "Value is too large".to_string(),
+                },
+                LogEntry {
+                    level: LogLevel::Panic,
+                    message: "panicked at src/lib.rs:1:1".to_string(),
+                },
+            ],
+        };
+
+        assert_eq!(logs.panic(), Some("panicked at src/lib.rs:1:1"));
+        assert_eq!(
+            logs.to_markdown(),
+            "```text\n[warn] Value is too large\n[panic] panicked at src/lib.rs:1:1\n```"
+        );
+        assert_eq!(StepLogs::default().panic(), None);
+    }
+}
@@ -0,0 +1,218 @@
+//! Coverage of the stories with the plays and descriptions
+//!
+//! Coverage doesn't run anything. It lists every story in the [RouteDef] tree
+//! with the number of its plays and steps, and checks if the story has a real
+//! description instead of the cheat sheet returned by the default
+//! [Story::description][crate::Story::description].
+
+use serde::Serialize;
+
+use super::stories;
+use crate::RouteDef;
+use crate::story::is_default_description;
+
+/// State of the description of the story
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DescriptionStatus {
+    /// Story has its own description
+    Custom,
+    /// Story still shows the "New Story" cheat sheet
+    Default,
+    /// Description of the story is empty
+    Missing,
+}
+
+impl DescriptionStatus {
+    /// Checks the description of the story
+    pub fn of(description: &str) -> Self {
+        if description.trim().is_empty() {
+            DescriptionStatus::Missing
+        } else if is_default_description(description) {
+            DescriptionStatus::Default
+        } else {
+            DescriptionStatus::Custom
+        }
+    }
+}
+
+/// Coverage of the single story
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StoryCoverage {
+    /// Path to the story in the application
+    pub path: String,
+    /// Label of the story in the menu
+    pub label: String,
+    /// Number of plays of the story
+    pub plays: usize,
+    /// Number of steps in all of the plays, including the setup and teardown steps
+    pub steps: usize,
+    /// State of the description of the story
+    pub description: DescriptionStatus,
+}
+
+impl StoryCoverage {
+    /// Returns the list of problems with the story
+    ///
+    /// Story is covered if it has at least one play and its own description.
+    pub fn issues(&self) -> Vec<&'static str> {
+        let mut issues = Vec::new();
+
+        if self.plays == 0 {
+            issues.push("no plays");
+        }
+
+        match self.description {
+            DescriptionStatus::Custom => {}
+            DescriptionStatus::Default => issues.push("default description"),
+            DescriptionStatus::Missing => issues.push("missing description"),
+        }
+
+        issues
+    }
+
+    /// Returns `true` if the story has at least one play and its own description
+    pub fn is_covered(&self) -> bool {
+        self.issues().is_empty()
+    }
+}
+
+/// Coverage of all of the stories in the application
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CoverageReport {
+    /// Coverage of every story, in the same order as they show up in the menu
+    pub stories: Vec<StoryCoverage>,
+}
+
+impl CoverageReport {
+    /// Returns the stories which don't have a play or a description
+    pub fn uncovered(&self) -> impl Iterator<Item = &StoryCoverage> {
+        self.stories.iter().filter(|story| !story.is_covered())
+    }
+
+    /// Returns `true` if every story has at least one play and its own description
+    pub fn is_complete(&self) -> bool {
+        self.uncovered().next().is_none()
+    }
+
+    /// Short, human readable summary of the coverage
+    ///
+    /// For example `10 of 12 stories covered, 25 plays, 140 steps`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} of {} stories covered, {} plays, {} steps",
+            self.stories.len() - self.uncovered().count(),
+            self.stories.len(),
+            self.stories.iter().map(|story| story.plays).sum::<usize>(),
+            self.stories.iter().map(|story| story.steps).sum::<usize>(),
+        )
+    }
+
+    /// Exports the report as a JSON document
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the report couldn't be serialized
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Returns the coverage of every story in the [RouteDef] tree
+///
+/// You can use it in the `wasm-bindgen-test` to enforce that every story has
+/// at least one play and a real description.
+///
+/// ```rust,ignore
+/// #[wasm_bindgen_test]
+/// fn every_story_is_covered() {
+///     let report = coverage(&routes());
+///
+///     let uncovered = report
+///         .uncovered()
+///         .map(|story| format!("{}: {}", story.path, story.issues().join(", ")))
+///         .collect::<Vec<_>>();
+///
+///     assert!(uncovered.is_empty(), "{}", uncovered.join("\n"));
+/// }
+/// ```
+pub fn coverage(routes: &[RouteDef]) -> CoverageReport {
+    let stories = stories(routes)
+        .into_iter()
+        .map(|story| {
+            let plays = story.suite().plays();
+
+            StoryCoverage {
+                path: story.path().to_string(),
+                label: story.label().to_string(),
+                plays: plays.len(),
+                steps: plays.iter().map(|play| play.steps.len()).sum(),
+                description: DescriptionStatus::of(story.suite().description()),
+            }
+        })
+        .collect();
+
+    CoverageReport { stories }
+}
+
+#[cfg(test)]
+/// Tests of the story coverage
+mod tests {
+    use super::CoverageReport;
+    use super::DescriptionStatus;
+    use super::StoryCoverage;
+    use crate::Story;
+
+    /// Story which doesn't override any of the default methods
+    #[derive(Debug, Default, Clone, Copy)]
+    struct DefaultStory;
+
+    impl Story for DefaultStory {}
+
+    /// Cheat sheet of the new story is recognized as the default description
+    #[test]
+    fn description_status() {
+        assert_eq!(
+            DescriptionStatus::of(DefaultStory.description()),
+            DescriptionStatus::Default
+        );
+        assert_eq!(DescriptionStatus::of("  \n"), DescriptionStatus::Missing);
+        assert_eq!(DescriptionStatus::of("# Button"), DescriptionStatus::Custom);
+    }
+
+    /// Creates the coverage of a story
+    fn story_coverage(path: &str, plays: usize, description: DescriptionStatus) -> StoryCoverage {
+        StoryCoverage {
+            path: path.to_string(),
+            label: path.to_string(),
+            plays,
+            steps: plays * 3,
+            description,
+        }
+    }
+
+    /// Stories without plays or with the default description are not covered
+    #[test]
+    fn coverage_issues() {
+        let covered = story_coverage("/a", 1, DescriptionStatus::Custom);
+        let uncovered = story_coverage("/b", 0, DescriptionStatus::Default);
+
+        assert!(covered.is_covered());
+        assert_eq!(uncovered.issues(), vec!["no plays", "default description"]);
+    }
+
+    /// Summary counts the covered stories, plays and steps
+    #[test]
+    fn coverage_summary() {
+        let report = CoverageReport {
+            stories: vec![
+                story_coverage("/a", 2, DescriptionStatus::Custom),
+                story_coverage("/b", 1, DescriptionStatus::Missing),
+            ],
+        };
+
+        assert!(!report.is_complete());
+        assert_eq!(report.uncovered().count(), 1);
+        assert_eq!(report.summary(), "1 of 2 stories covered, 3 plays, 9 steps");
+    }
+}
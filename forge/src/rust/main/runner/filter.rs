@@ -0,0 +1,141 @@
+//! Selecting the plays by their tags and descriptions
+//!
+
+use std::fmt::Display;
+
+/// Prefix of the tag in the filter query
+const TAG_PREFIX: char = '#';
+/// Prefix of the excluded tag in the filter query
+const EXCLUDE_PREFIX: char = '-';
+
+/// Selects the plays which should be run or shown
+///
+/// Play matches the filter if it has all of the [tags][PlayFilter::tags], none of
+/// the [excluded tags][PlayFilter::excluded_tags], and its description contains
+/// the [text][PlayFilter::text]. Empty filter matches every play.
+///
+/// Filter can be written as a query, like `#keyboard -#slow submit`, see
+/// [PlayFilter::parse].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayFilter {
+    /// Tags which the play must have
+    pub tags: Vec<String>,
+    /// Tags which the play must not have
+    pub excluded_tags: Vec<String>,
+    /// Text which must be a part of the description of the play
+    ///
+    /// Texts are compared ignoring the case.
+    pub text: Option<String>,
+}
+
+impl PlayFilter {
+    /// Parses the filter from the query
+    ///
+    /// - `#tag` - play must have the tag
+    /// - `-#tag` - play must not have the tag
+    /// - any other words - text which must be a part of the description
+    ///
+    /// ```rust,ignore
+    /// let filter = PlayFilter::parse("#keyboard -#slow submit");
+    ///
+    /// assert_eq!(filter.tags, vec!["keyboard"]);
+    /// assert_eq!(filter.excluded_tags, vec!["slow"]);
+    /// assert_eq!(filter.text.as_deref(), Some("submit"));
+    /// ```
+    pub fn parse(query: &str) -> Self {
+        let mut filter = Self::default();
+        let mut words = Vec::new();
+
+        for word in query.split_whitespace() {
+            let excluded = word
+                .strip_prefix(EXCLUDE_PREFIX)
+                .and_then(|word| word.strip_prefix(TAG_PREFIX));
+
+            if let Some(tag) = excluded {
+                if !tag.is_empty() {
+                    filter.excluded_tags.push(tag.to_string());
+                }
+            } else if let Some(tag) = word.strip_prefix(TAG_PREFIX) {
+                if !tag.is_empty() {
+                    filter.tags.push(tag.to_string());
+                }
+            } else {
+                words.push(word);
+            }
+        }
+
+        if !words.is_empty() {
+            filter.text = Some(words.join(" "));
+        }
+
+        filter
+    }
+
+    /// Returns `true` if the filter matches every play
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.excluded_tags.is_empty() && self.text.is_none()
+    }
+
+    /// Returns `true` if the play with the `description` and the `tags` matches the filter
+    pub fn matches(&self, description: &str, tags: &[&str]) -> bool {
+        let has_tag = |expected: &String| tags.iter().any(|tag| tag.eq_ignore_ascii_case(expected));
+
+        self.tags.iter().all(has_tag)
+            && !self.excluded_tags.iter().any(has_tag)
+            && self
+                .text
+                .as_ref()
+                .is_none_or(|text| description.to_lowercase().contains(&text.to_lowercase()))
+    }
+}
+
+impl Display for PlayFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tags = self.tags.iter().map(|tag| format!("{TAG_PREFIX}{tag}"));
+        let excluded_tags = self
+            .excluded_tags
+            .iter()
+            .map(|tag| format!("{EXCLUDE_PREFIX}{TAG_PREFIX}{tag}"));
+
+        let query = tags
+            .chain(excluded_tags)
+            .chain(self.text.clone())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        f.write_str(&query)
+    }
+}
+
+#[cfg(test)]
+/// Tests of the play filter
+mod tests {
+    use super::PlayFilter;
+
+    /// Tags, excluded tags and the text are read from the query
+    #[test]
+    fn parse_filter() {
+        let filter = PlayFilter::parse("  #keyboard -#slow submit  the form #smoke # -#");
+
+        assert_eq!(filter.tags, vec!["keyboard", "smoke"]);
+        assert_eq!(filter.excluded_tags, vec!["slow"]);
+        assert_eq!(filter.text.as_deref(), Some("submit the form"));
+        assert_eq!(
+            filter.to_string(),
+            "#keyboard #smoke -#slow submit the form"
+        );
+        assert!(PlayFilter::parse(" ").is_empty());
+    }
+
+    /// Play must have all of the tags, none of the excluded ones and contain the text
+    #[test]
+    fn filter_matches() {
+        let filter = PlayFilter::parse("#Keyboard -#slow SUBMIT");
+
+        assert!(filter.matches("Form can be submitted", &["keyboard", "smoke"]));
+        assert!(!filter.matches("Form can be submitted", &["smoke"]));
+        assert!(!filter.matches("Form can be submitted", &["keyboard", "slow"]));
+        assert!(!filter.matches("Form can be reset", &["keyboard"]));
+        assert!(PlayFilter::default().matches("Any play", &[]));
+    }
+}
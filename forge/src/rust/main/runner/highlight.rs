@@ -0,0 +1,340 @@
+//! Outlining of the elements the step is acting on
+//!
+//! Queries and user events [mark][mark_target] the elements they have found or
+//! interacted with. After the step has run, the test viewer outlines the last
+//! marked elements and labels the first outline with the name of the step. The
+//! accessibility audit uses the same outlines to show the offending elements.
+//!
+//! Outlines are drawn next to the canvas, not inside of it, so they never show
+//! up in the queries or in the DOM changes of the step. They are positioned
+//! relative to the parent of the canvas, which must be positioned, and they
+//! follow the content of the canvas when it's scrolled.
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+use leptos::prelude::document;
+use leptos::prelude::set_timeout;
+use leptos::wasm_bindgen::JsCast;
+use leptos::wasm_bindgen::closure::Closure;
+use leptos::web_sys::Element;
+use leptos::web_sys::HtmlElement;
+
+/// How long the outline of the passed step stays visible
+const HIGHLIGHT_DURATION: Duration = Duration::from_millis(1500);
+/// Color of the outline of the passed step
+const PASSED_COLOR: &str = "rgb(37, 99, 235)";
+/// Color of the outline of the failed step
+const FAILED_COLOR: &str = "rgb(220, 38, 38)";
+
+/// Outlines which are shown right now
+struct Highlight {
+    /// Canvas whose scrolling moves the outlines
+    canvas: HtmlElement,
+    /// Outlined elements together with their outlines
+    outlines: Vec<(Element, Element)>,
+    /// Color of the outlines
+    color: &'static str,
+    /// Listener moving the outlines when the canvas is scrolled
+    on_scroll: Closure<dyn Fn()>,
+}
+
+impl Highlight {
+    /// Moves every outline over its element
+    fn update(&self) {
+        update_outlines(&self.outlines, self.color);
+    }
+
+    /// Removes the outlines and stops following the scrolling of the canvas
+    fn remove(self) {
+        // If the listener can't be removed, there is nothing else we can do about it
+        let _ = self
+            .canvas
+            .remove_event_listener_with_callback("scroll", self.on_scroll.as_ref().unchecked_ref());
+
+        for (_, outline) in self.outlines {
+            outline.remove();
+        }
+    }
+}
+
+thread_local! {
+    /// Elements the running step has acted on last
+    static TARGETS: RefCell<Vec<Element>> = const { RefCell::new(Vec::new()) };
+    /// Outlines which are shown right now
+    static HIGHLIGHT: RefCell<Option<Highlight>> = const { RefCell::new(None) };
+}
+
+/// Remembers the `element` as the one the running step is acting on
+pub(crate) fn mark_target(element: &Element) {
+    mark_targets(std::slice::from_ref(element));
+}
+
+/// Remembers the `elements` as the ones the running step is acting on
+///
+/// Nothing is marked if there are no `elements`, so the elements marked before
+/// stay outlined when the step checks that something is gone.
+pub(crate) fn mark_targets<E: AsRef<Element>>(elements: &[E]) {
+    if elements.is_empty() {
+        return;
+    }
+
+    TARGETS.with(|targets| {
+        if let Ok(mut targets) = targets.try_borrow_mut() {
+            *targets = elements
+                .iter()
+                .map(|element| element.as_ref().clone())
+                .collect();
+        }
+    });
+}
+
+/// Returns the elements marked last and forgets them
+///
+/// Call it before the step is run to forget the elements marked by the previous steps.
+pub(crate) fn take_targets() -> Vec<Element> {
+    TARGETS.with(|targets| {
+        targets
+            .try_borrow_mut()
+            .map(|mut targets| std::mem::take(&mut *targets))
+            .unwrap_or_default()
+    })
+}
+
+/// Removes the outlines which are shown right now
+pub(crate) fn clear_highlight() {
+    let highlight = HIGHLIGHT.with(|highlight| {
+        highlight
+            .try_borrow_mut()
+            .ok()
+            .and_then(|mut highlight| highlight.take())
+    });
+
+    if let Some(highlight) = highlight {
+        highlight.remove();
+    }
+}
+
+/// Box of the outline in pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OutlineBox {
+    /// Distance of the left edge from the left edge of the origin
+    left: f64,
+    /// Distance of the top edge from the top edge of the origin
+    top: f64,
+    /// Width of the box
+    width: f64,
+    /// Height of the box
+    height: f64,
+}
+
+impl OutlineBox {
+    /// Box of the `element` measured in the viewport
+    fn of(element: &Element) -> Self {
+        let rect = element.get_bounding_client_rect();
+
+        Self {
+            left: rect.left(),
+            top: rect.top(),
+            width: rect.width(),
+            height: rect.height(),
+        }
+    }
+
+    /// Moves the box measured in the viewport inside of the `container` measured in the viewport
+    ///
+    /// The box is positioned relative to the padding box of the container, so
+    /// its `border` is left out, and the box stays at the same place of the
+    /// content of the container when the container is `scroll`ed.
+    fn inside(self, container: Self, border: (f64, f64), scroll: (f64, f64)) -> Self {
+        Self {
+            left: self.left - container.left - border.0 + scroll.0,
+            top: self.top - container.top - border.1 + scroll.1,
+            ..self
+        }
+    }
+
+    /// Inline style positioning the outline
+    fn style(&self, color: &str) -> String {
+        format!(
+            "position: absolute; left: {}px; top: {}px; width: {}px; height: {}px; \
+             outline: 2px solid {color}; pointer-events: none; z-index: 50;",
+            self.left, self.top, self.width, self.height,
+        )
+    }
+}
+
+/// Moves the outlines of the `color` over their elements
+fn update_outlines(outlines: &[(Element, Element)], color: &str) {
+    for (target, outline) in outlines {
+        let Some(parent) = outline.parent_element() else {
+            continue;
+        };
+
+        let outline_box = OutlineBox::of(target).inside(
+            OutlineBox::of(&parent),
+            (
+                f64::from(parent.client_left()),
+                f64::from(parent.client_top()),
+            ),
+            (
+                f64::from(parent.scroll_left()),
+                f64::from(parent.scroll_top()),
+            ),
+        );
+
+        let _ = outline.set_attribute("style", &outline_box.style(color));
+    }
+}
+
+/// Outlines the `targets` in the `canvas` and labels the first one with the `label`
+///
+/// The outlines of the passed step disappear after a moment, the outlines of
+/// the `failed` step are kept until the next step is outlined. Targets which
+/// are no longer a part of the canvas, for example because the step has removed
+/// them, are not outlined.
+pub(crate) fn highlight(canvas: &HtmlElement, targets: &[Element], label: &str, failed: bool) {
+    clear_highlight();
+
+    let Some(parent) = canvas.parent_element() else {
+        return;
+    };
+    let color = if failed { FAILED_COLOR } else { PASSED_COLOR };
+
+    let mut outlines = Vec::new();
+    for target in targets {
+        if !target.is_connected() || !canvas.contains(Some(target)) {
+            continue;
+        }
+
+        // Highlight is only a hint, so the failure to draw it is not a problem
+        let Ok(outline) = document().create_element("div") else {
+            continue;
+        };
+        outline.set_class_name("leptos-forge-step-highlight");
+
+        if outlines.is_empty()
+            && let Ok(text) = document().create_element("span")
+        {
+            text.set_class_name("leptos-forge-step-highlight-label");
+            text.set_text_content(Some(label));
+            let _ = text.set_attribute(
+                "style",
+                &format!(
+                    "position: absolute; left: -2px; bottom: calc(100% + 2px); padding: 0 4px; \
+                     background: {color}; color: white; font-size: 12px; white-space: nowrap;"
+                ),
+            );
+            let _ = outline.append_child(&text);
+        }
+
+        if parent.append_child(&outline).is_ok() {
+            outlines.push((target.clone(), outline));
+        }
+    }
+
+    if outlines.is_empty() {
+        return;
+    }
+
+    let following = outlines.clone();
+    let on_scroll = Closure::<dyn Fn()>::new(move || update_outlines(&following, color));
+    let _ = canvas.add_event_listener_with_callback("scroll", on_scroll.as_ref().unchecked_ref());
+
+    let highlight = Highlight {
+        canvas: canvas.clone(),
+        outlines,
+        color,
+        on_scroll,
+    };
+    highlight.update();
+
+    if !failed {
+        let shown = highlight
+            .outlines
+            .first()
+            .map(|(_, outline)| outline.clone());
+        set_timeout(
+            move || {
+                // Only the outlines of this step are removed, not the ones drawn later
+                let current = HIGHLIGHT.with(|current| {
+                    current.try_borrow().ok().and_then(|current| {
+                        current
+                            .as_ref()
+                            .and_then(|current| current.outlines.first())
+                            .map(|(_, outline)| outline.clone())
+                    })
+                });
+                if current.is_some() && current == shown {
+                    clear_highlight();
+                }
+            },
+            HIGHLIGHT_DURATION,
+        );
+    }
+
+    HIGHLIGHT.with(|current| {
+        if let Ok(mut current) = current.try_borrow_mut() {
+            *current = Some(highlight);
+        }
+    });
+}
+
+#[cfg(test)]
+/// Tests of the outlines
+mod tests {
+    use super::OutlineBox;
+
+    /// Outline is placed in the padding box of the container and moves with its scrolled content
+    #[test]
+    fn outline_inside_container() {
+        let target = OutlineBox {
+            left: 120.0,
+            top: 80.0,
+            width: 40.0,
+            height: 20.0,
+        };
+        let container = OutlineBox {
+            left: 100.0,
+            top: 50.0,
+            width: 400.0,
+            height: 300.0,
+        };
+
+        assert_eq!(
+            target.inside(container, (0.0, 0.0), (0.0, 0.0)),
+            OutlineBox {
+                left: 20.0,
+                top: 30.0,
+                width: 40.0,
+                height: 20.0,
+            }
+        );
+        assert_eq!(
+            target.inside(container, (2.0, 1.0), (10.0, 200.0)),
+            OutlineBox {
+                left: 28.0,
+                top: 229.0,
+                width: 40.0,
+                height: 20.0,
+            }
+        );
+    }
+
+    /// Outline is absolutely positioned, so it isn't moved when the page is scrolled
+    #[test]
+    fn outline_style() {
+        let outline = OutlineBox {
+            left: 28.0,
+            top: 229.5,
+            width: 40.0,
+            height: 20.0,
+        };
+
+        assert_eq!(
+            outline.style("red"),
+            "position: absolute; left: 28px; top: 229.5px; width: 40px; height: 20px; \
+             outline: 2px solid red; pointer-events: none; z-index: 50;"
+        );
+    }
+}
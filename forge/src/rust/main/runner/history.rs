@@ -0,0 +1,164 @@
+//! History of the results of the plays run in the test viewer
+//!
+//! The last [HISTORY_LENGTH] results of every play are kept in the `localStorage`
+//! of the browser, keyed by the path of the story and the description of the
+//! play, so they survive reloading the page. The history shows how often the play
+//! passes and flags the plays whose outcome varies between the runs.
+
+use leptos::prelude::window;
+
+/// Number of the results kept for every play
+pub(crate) const HISTORY_LENGTH: usize = 20;
+
+/// Prefix of the keys under which the histories are stored in the `localStorage`
+const STORAGE_PREFIX: &str = "leptos-forge-history";
+
+/// Stored character of the passed run
+const PASSED: char = 'P';
+/// Stored character of the failed run
+const FAILED: char = 'F';
+
+/// Results of the last runs of the play, the oldest first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct PlayHistory {
+    /// `true` for every passed run and `false` for every failed one
+    runs: Vec<bool>,
+}
+
+impl PlayHistory {
+    /// Adds the result of the run, forgetting the oldest one if the history is full
+    pub(crate) fn push(&mut self, passed: bool) {
+        self.runs.push(passed);
+        if self.runs.len() > HISTORY_LENGTH {
+            let excess = self.runs.len() - HISTORY_LENGTH;
+            self.runs.drain(..excess);
+        }
+    }
+
+    /// Returns `true` if the play wasn't run yet
+    pub(crate) fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Percentage of the remembered runs which have passed, `None` if there are none
+    pub(crate) fn pass_rate(&self) -> Option<f64> {
+        (!self.runs.is_empty()).then(|| {
+            let passed = self.runs.iter().filter(|passed| **passed).count();
+            passed as f64 * 100.0 / self.runs.len() as f64
+        })
+    }
+
+    /// Returns `true` if the play has both passed and failed in the remembered runs
+    pub(crate) fn is_flaky(&self) -> bool {
+        self.runs.iter().any(|passed| *passed) && self.runs.iter().any(|passed| !*passed)
+    }
+
+    /// Runs drawn as bars, high for the passed runs and low for the failed ones
+    pub(crate) fn sparkline(&self) -> String {
+        self.runs
+            .iter()
+            .map(|passed| if *passed { '▇' } else { '▁' })
+            .collect()
+    }
+
+    /// Short summary of the history, like `95% of 20 runs`
+    pub(crate) fn summary(&self) -> Option<String> {
+        self.pass_rate().map(|rate| {
+            let runs = if self.runs.len() == 1 { "run" } else { "runs" };
+            format!("{rate:.0}% of {} {runs}", self.runs.len())
+        })
+    }
+
+    /// Writes the history as the value stored in the `localStorage`
+    pub(crate) fn to_storage(&self) -> String {
+        self.runs
+            .iter()
+            .map(|passed| if *passed { PASSED } else { FAILED })
+            .collect()
+    }
+
+    /// Reads the history from the value stored in the `localStorage`
+    ///
+    /// Unknown characters are skipped, so a damaged value never breaks the test viewer.
+    pub(crate) fn from_storage(value: &str) -> Self {
+        let mut history = Self::default();
+        for run in value.chars() {
+            match run {
+                PASSED => history.push(true),
+                FAILED => history.push(false),
+                _ => {}
+            }
+        }
+        history
+    }
+
+    /// Loads the history of the `play` of the story with the `path`
+    ///
+    /// Empty history is returned if the `localStorage` isn't available.
+    pub(crate) fn load(path: &str, play: &str) -> Self {
+        let stored = window()
+            .local_storage()
+            .ok()
+            .flatten()
+            .and_then(|storage| storage.get_item(&storage_key(path, play)).ok().flatten());
+
+        stored
+            .map(|value| Self::from_storage(&value))
+            .unwrap_or_default()
+    }
+
+    /// Saves the history of the `play` of the story with the `path`
+    ///
+    /// Nothing is saved if the `localStorage` isn't available or it's full.
+    pub(crate) fn save(&self, path: &str, play: &str) {
+        if let Ok(Some(storage)) = window().local_storage() {
+            let _ = storage.set_item(&storage_key(path, play), &self.to_storage());
+        }
+    }
+}
+
+/// Key under which the history of the `play` of the story with the `path` is stored
+pub(crate) fn storage_key(path: &str, play: &str) -> String {
+    format!("{STORAGE_PREFIX}:{path}#{play}")
+}
+
+#[cfg(test)]
+/// Tests of the play history
+mod tests {
+    use super::HISTORY_LENGTH;
+    use super::PlayHistory;
+    use super::storage_key;
+
+    /// Only the last runs are remembered and the play which both passed and failed is flaky
+    #[test]
+    fn play_history() {
+        let mut history = PlayHistory::default();
+        assert_eq!(history.pass_rate(), None);
+        assert!(!history.is_flaky());
+
+        for _ in 0..HISTORY_LENGTH {
+            history.push(false);
+        }
+        history.push(true);
+        history.push(true);
+
+        assert_eq!(history.to_storage().len(), HISTORY_LENGTH);
+        assert!(history.to_storage().ends_with("FFPP"));
+        assert_eq!(history.summary().as_deref(), Some("10% of 20 runs"));
+        assert!(history.is_flaky());
+    }
+
+    /// History is stored as the passed and failed runs and damaged values are ignored
+    #[test]
+    fn stored_play_history() {
+        let history = PlayHistory::from_storage("PPxF");
+
+        assert_eq!(history.to_storage(), "PPF");
+        assert_eq!(history.sparkline(), "▇▇▁");
+        assert_eq!(history.summary().as_deref(), Some("67% of 3 runs"));
+        assert_eq!(
+            storage_key("/buttons/primary", "Click is counted"),
+            "leptos-forge-history:/buttons/primary#Click is counted"
+        );
+    }
+}
@@ -0,0 +1,583 @@
+//! Runs the plays of the stories without the UI
+//!
+//! The runner walks the whole [RouteDef] tree, mounts every story and runs all
+//! of its plays. The result is a [TestReport] which can be exported as JSON or
+//! JUnit XML, so you can run all of your plays on the CI.
+//!
+//! # Running the plays using `wasm-bindgen-test`
+//!
+//! ```rust,ignore
+//! use leptos_forge::runner::RunOptions;
+//! use leptos_forge::runner::run;
+//! use wasm_bindgen_test::wasm_bindgen_test;
+//! use wasm_bindgen_test::wasm_bindgen_test_configure;
+//!
+//! wasm_bindgen_test_configure!(run_in_browser);
+//!
+//! #[wasm_bindgen_test]
+//! async fn all_plays() {
+//!     // the same routes you pass to the `App` component
+//!     let routes = routes();
+//!
+//!     let report = run(&routes, &RunOptions::default()).await;
+//!
+//!     web_sys::console::log_1(&report.to_junit().into());
+//!
+//!     assert!(report.is_success(), "{}", report.summary());
+//! }
+//! ```
+//!
+//! # Running only some of the plays
+//!
+//! Plays can be selected by their [tags][crate::Play::tags] and descriptions
+//! with the [PlayFilter], for example to skip the slow plays
+//!
+//! ```rust,ignore
+//! let options = RunOptions {
+//!     filter: PlayFilter::parse("#smoke -#slow"),
+//!     ..RunOptions::default()
+//! };
+//!
+//! let report = run(&routes, &options).await;
+//! ```
+
+mod capture;
+mod coverage;
+mod filter;
+mod highlight;
+mod history;
+mod mutations;
+mod recorder;
+mod report;
+#[cfg(test)]
+mod tests;
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::time::Duration;
+
+use leptos::mount::mount_to;
+use leptos::prelude::*;
+use leptos::wasm_bindgen::JsCast;
+use leptos::web_sys::HtmlElement;
+
+pub(crate) use capture::ConsoleCapture;
+pub(crate) use capture::StepLogs;
+pub(crate) use capture::on_panic;
+pub use coverage::CoverageReport;
+pub use coverage::DescriptionStatus;
+pub use coverage::StoryCoverage;
+pub use coverage::coverage;
+pub use filter::PlayFilter;
+pub(crate) use highlight::clear_highlight;
+pub(crate) use highlight::highlight;
+pub(crate) use highlight::mark_target;
+pub(crate) use highlight::mark_targets;
+pub(crate) use highlight::take_targets;
+pub(crate) use history::PlayHistory;
+pub(crate) use mutations::DomDiff;
+pub(crate) use mutations::MutationRecorder;
+pub(crate) use recorder::RecordedAction;
+pub(crate) use recorder::Recorder;
+pub(crate) use recorder::play_code;
+pub(crate) use recorder::push_action;
+pub use report::PlayReport;
+pub use report::Status;
+pub use report::StepReport;
+pub use report::StoryReport;
+pub use report::TestReport;
+pub(crate) use report::format_duration;
+
+use crate::DEFAULT_STEP_TIMEOUT;
+use crate::IntoStory;
+use crate::POLL_INTERVAL;
+use crate::Play;
+use crate::RouteDef;
+use crate::Step;
+use crate::StepContext;
+use crate::StepFailure;
+use crate::StepResult;
+use crate::Story;
+use crate::sleep;
+use crate::story::deadline;
+use crate::story::next_frame;
+use crate::story::now;
+use crate::story::remaining;
+use crate::story::with_timeout;
+
+/// Future returned by the [TestSuite::run_play]
+pub type PlayFuture<'a> = Pin<Box<dyn Future<Output = PlayReport> + 'a>>;
+
+/// Short description of the play
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayInfo {
+    /// Description of the play
+    pub description: &'static str,
+    /// Descriptions of the steps in the play
+    pub steps: Vec<&'static str>,
+    /// Tags of the play
+    pub tags: Vec<&'static str>,
+}
+
+impl PlayInfo {
+    /// Returns `true` if the play matches the `filter`
+    pub fn matches(&self, filter: &PlayFilter) -> bool {
+        filter.matches(self.description, &self.tags)
+    }
+}
+
+/// Type erased access to the plays of the single story
+///
+/// Stories added with [RouteDef::story] and [RouteDef::private] provide the
+/// test suite, so the runner can work with them without knowing their type.
+pub trait TestSuite {
+    /// Returns the list of plays in the story
+    fn plays(&self) -> Vec<PlayInfo>;
+
+    /// Returns the description of the story
+    ///
+    /// Suites which don't have a description return an empty string.
+    fn description(&self) -> &'static str {
+        ""
+    }
+
+    /// Creates a fresh instance of the story, mounts it inside of the `container`
+    /// and runs the play with the given index
+    ///
+    /// The story is unmounted and removed from the `container` after the play
+    /// is completed.
+    fn run_play<'a>(&'a self, play: usize, container: &'a HtmlElement) -> PlayFuture<'a>;
+}
+
+/// Implementation of the [TestSuite] for the [Story]
+pub struct StorySuite<S: IntoStory> {
+    /// Story to be tested
+    _story: PhantomData<S>,
+}
+
+impl<S: IntoStory> StorySuite<S> {
+    /// Create new instance of the StorySuite
+    pub fn new() -> Self {
+        Self {
+            _story: PhantomData,
+        }
+    }
+}
+
+impl<S: IntoStory> Default for StorySuite<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: 'static + IntoStory> TestSuite for StorySuite<S> {
+    fn plays(&self) -> Vec<PlayInfo> {
+        let owner = Owner::new();
+
+        let plays = owner.with(|| {
+            let story = S::default().into_story();
+
+            story
+                .plays()
+                .iter()
+                .map(|play| PlayInfo {
+                    description: play.description(),
+                    steps: schedule(&story, play.as_ref())
+                        .iter()
+                        .map(|scheduled| scheduled.step.description())
+                        .collect(),
+                    tags: play.tags(),
+                })
+                .collect()
+        });
+
+        owner.cleanup();
+
+        plays
+    }
+
+    fn description(&self) -> &'static str {
+        let owner = Owner::new();
+        let description = owner.with(|| S::default().into_story().description());
+        owner.cleanup();
+
+        description
+    }
+
+    fn run_play<'a>(&'a self, play: usize, container: &'a HtmlElement) -> PlayFuture<'a> {
+        Box::pin(run_story_play::<S>(play, container))
+    }
+}
+
+/// Step of the play together with its role in the play
+pub(crate) struct ScheduledStep<S: Story> {
+    /// Step to run
+    pub(crate) step: Box<dyn Step<Story = S>>,
+    /// `true` for the teardown steps which must be run even if the play has failed
+    pub(crate) teardown: bool,
+}
+
+impl<S: Story> ScheduledStep<S> {
+    /// Returns `true` if the step isn't run, because one of the previous steps has `failed`
+    ///
+    /// Teardown steps are run even after the failure.
+    pub(crate) fn is_skipped(&self, failed: bool) -> bool {
+        failed && !self.teardown
+    }
+}
+
+/// Returns the steps of the play surrounded by the setup and teardown steps
+///
+/// Steps are run in the following order
+///
+/// 1. [Story::before_each]
+/// 2. [Play::before]
+/// 3. [Play::steps]
+/// 4. [Play::after]
+/// 5. [Story::after_each]
+///
+/// After the first failure only the teardown steps are run.
+pub(crate) fn schedule<S: Story>(story: &S, play: &dyn Play<Story = S>) -> Vec<ScheduledStep<S>> {
+    let setup = story.before_each().into_iter().chain(play.before());
+    let teardown = play.after().into_iter().chain(story.after_each());
+
+    setup
+        .chain(play.steps())
+        .map(|step| ScheduledStep {
+            step,
+            teardown: false,
+        })
+        .chain(teardown.map(|step| ScheduledStep {
+            step,
+            teardown: true,
+        }))
+        .collect()
+}
+
+/// Outcome of running a single step
+pub(crate) struct StepOutcome {
+    /// Result returned by the step
+    pub(crate) result: StepResult,
+    /// How long it took to run the step in milliseconds
+    pub(crate) duration_ms: f64,
+}
+
+/// Runs a single step and measures how long it took
+///
+/// This is the common part of running the step shared by the test viewer and
+/// the runner.
+///
+/// If the step defines the [Step::timeout], it's retried every [POLL_INTERVAL]
+/// until it passes or the timeout expires. Otherwise it's run once and fails if
+/// it doesn't complete within the [DEFAULT_STEP_TIMEOUT].
+///
+/// Effects write to the DOM on a later microtask, so the clock is stopped only
+/// when the browser is about to paint the next frame. The duration includes the
+/// render triggered by the step.
+///
+/// The step which has passed, but took longer than its [Step::budget], fails.
+pub(crate) async fn execute_step<S: Story>(
+    step: &dyn Step<Story = S>,
+    ctx: &mut StepContext<S>,
+) -> StepOutcome {
+    let start = now();
+
+    let result = match step.timeout() {
+        Some(timeout) => retry_step(step, ctx, timeout).await,
+        None => with_timeout(step.run_async(ctx), DEFAULT_STEP_TIMEOUT)
+            .await
+            .unwrap_or_else(|| Err(StepFailure::timed_out(DEFAULT_STEP_TIMEOUT, None))),
+    };
+
+    next_frame().await;
+    let duration_ms = now() - start;
+    let result = match (result, step.budget()) {
+        (Ok(()), Some(budget)) if duration_ms > budget.as_secs_f64() * 1000.0 => {
+            Err(StepFailure::over_budget(budget, duration_ms))
+        }
+        (result, _) => result,
+    };
+
+    StepOutcome {
+        result,
+        duration_ms,
+    }
+}
+
+/// Runs the step until it passes or the `timeout` expires
+///
+/// Every attempt is limited by the time left until the deadline, so an attempt
+/// which never completes fails the step instead of blocking the test run.
+async fn retry_step<S: Story>(
+    step: &dyn Step<Story = S>,
+    ctx: &mut StepContext<S>,
+    timeout: Duration,
+) -> StepResult {
+    let deadline = deadline(timeout);
+    let mut last = None;
+
+    while let Some(left) = remaining(deadline) {
+        match with_timeout(step.run_async(ctx), left).await {
+            Some(Ok(())) => return Ok(()),
+            Some(Err(failure)) => last = Some(failure),
+            None => break,
+        }
+
+        if let Some(left) = remaining(deadline) {
+            sleep(left.min(POLL_INTERVAL)).await;
+        }
+    }
+
+    Err(StepFailure::timed_out(timeout, last))
+}
+
+/// Creates the element in which the story will be mounted during the test run
+fn create_canvas(container: &HtmlElement) -> Result<HtmlElement, StepFailure> {
+    let Ok(canvas) = document().create_element("div") else {
+        return Err(StepFailure::new("Unable to create the canvas element"));
+    };
+    let canvas: HtmlElement = canvas.unchecked_into();
+    canvas.set_class_name("leptos-forge-canvas leptos-forge-runner-canvas");
+
+    if container.append_child(&canvas).is_err() {
+        return Err(StepFailure::new(
+            "Unable to add the canvas element to the container",
+        ));
+    }
+
+    Ok(canvas)
+}
+
+/// Mounts a fresh instance of the story and runs the play with the given index
+async fn run_story_play<S: 'static + IntoStory>(
+    play: usize,
+    container: &HtmlElement,
+) -> PlayReport {
+    let owner = Owner::new();
+    let start = now();
+
+    let story = owner.with(|| S::default().into_story());
+    let plays = owner.with(|| story.plays());
+
+    let Some(play_to_run) = plays.get(play) else {
+        owner.cleanup();
+
+        return PlayReport {
+            play: format!("Play #{play}"),
+            status: Status::Failed,
+            duration_ms: 0.0,
+            render_ms: 0.0,
+            steps: Vec::new(),
+            failure: Some(StepFailure::new("Play doesn't exist")),
+        };
+    };
+
+    let steps = owner.with(|| schedule(&story, play_to_run.as_ref()));
+    let mut report = PlayReport {
+        play: play_to_run.description().to_string(),
+        status: Status::Passed,
+        duration_ms: 0.0,
+        render_ms: 0.0,
+        steps: Vec::with_capacity(steps.len()),
+        failure: None,
+    };
+
+    let canvas = match create_canvas(container) {
+        Ok(canvas) => canvas,
+        Err(failure) => {
+            owner.cleanup();
+
+            report.status = Status::Failed;
+            report.failure = Some(failure);
+            return report;
+        }
+    };
+
+    let mount_start = now();
+    let mount = owner.with(|| mount_to(canvas.clone(), move || story.view()));
+    next_frame().await;
+    report.render_ms = now() - mount_start;
+
+    let mut ctx = StepContext::new(canvas.clone(), story);
+
+    for scheduled in &steps {
+        let step = &scheduled.step;
+
+        if scheduled.is_skipped(report.status == Status::Failed) {
+            report.steps.push(StepReport {
+                step: step.description().to_string(),
+                status: Status::Skipped,
+                duration_ms: 0.0,
+                failure: None,
+            });
+            continue;
+        }
+
+        let outcome = execute_step(step.as_ref(), &mut ctx).await;
+        let (status, failure) = match outcome.result {
+            Ok(()) => (Status::Passed, None),
+            Err(failure) => (Status::Failed, Some(failure)),
+        };
+
+        if status == Status::Failed {
+            report.status = Status::Failed;
+        }
+
+        report.steps.push(StepReport {
+            step: step.description().to_string(),
+            status,
+            duration_ms: outcome.duration_ms,
+            failure,
+        });
+    }
+
+    drop(mount);
+    canvas.remove();
+    owner.cleanup();
+
+    report.duration_ms = now() - start;
+    report
+}
+
+/// Options of the test run
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Element inside of which stories will be mounted during the test run
+    ///
+    /// If it's `None` then stories will be mounted in the document's body
+    pub container: Option<HtmlElement>,
+    /// Selects the plays which are run
+    ///
+    /// The empty filter, which is the default, runs every play.
+    pub filter: PlayFilter,
+}
+
+/// Story found in the [RouteDef] tree
+pub struct StoryEntry {
+    /// Path to the story in the application
+    path: String,
+    /// Label of the story in the menu
+    label: &'static str,
+    /// Tests of the story
+    suite: Box<dyn TestSuite>,
+}
+
+impl StoryEntry {
+    /// Path to the story in the application
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Label of the story in the menu
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    /// Tests of the story
+    pub fn suite(&self) -> &dyn TestSuite {
+        self.suite.as_ref()
+    }
+
+    /// Runs the plays of the story matching the [RunOptions::filter] one after another
+    ///
+    /// Plays which don't match the filter are reported as skipped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `options` don't provide the container and the document has no body
+    pub async fn run(&self, options: &RunOptions) -> StoryReport {
+        let container = container(options);
+        let mut plays = Vec::new();
+
+        for (play, info) in self.suite.plays().iter().enumerate() {
+            let report = if info.matches(&options.filter) {
+                self.suite.run_play(play, &container).await
+            } else {
+                PlayReport::skipped(info.description)
+            };
+            plays.push(report);
+        }
+
+        StoryReport {
+            path: self.path.clone(),
+            label: self.label.to_string(),
+            plays,
+        }
+    }
+}
+
+/// Returns the element inside of which stories should be mounted
+fn container(options: &RunOptions) -> HtmlElement {
+    options.container.clone().unwrap_or_else(|| {
+        document()
+            .body()
+            .expect("We are running csr mode. Document should exist and it should have a body")
+    })
+}
+
+/// Joins the path of the parent route with the path segment of the child
+///
+/// Segment `/` is a root segment and it doesn't contribute to the path
+fn join_path(prefix: &str, segment: &str) -> String {
+    if segment == "/" {
+        prefix.to_string()
+    } else {
+        format!("{prefix}/{segment}")
+    }
+}
+
+/// Recursively collects the stories from the routes
+fn collect_stories(routes: &[RouteDef], prefix: &str, stories: &mut Vec<StoryEntry>) {
+    for route in routes {
+        let path = join_path(prefix, route.path());
+
+        if let RouteDef::Route { label, suite, .. } = route
+            && let Some(suite) = suite()
+        {
+            stories.push(StoryEntry {
+                path: if path.is_empty() {
+                    "/".to_string()
+                } else {
+                    path.clone()
+                },
+                label: *label,
+                suite,
+            });
+        }
+
+        collect_stories(route.subroutes(), &path, stories);
+    }
+}
+
+/// Returns every story in the [RouteDef] tree
+///
+/// Stories are returned in the same order as they show up in the menu. Private
+/// stories are included too.
+pub fn stories(routes: &[RouteDef]) -> Vec<StoryEntry> {
+    let mut stories = Vec::new();
+    collect_stories(routes, "", &mut stories);
+    stories
+}
+
+/// Runs every play of every story in the [RouteDef] tree
+///
+/// Plays are run one after another. Every play gets a fresh instance of the
+/// story mounted in a new canvas. Only the plays matching the [RunOptions::filter]
+/// are run, every other play is reported as skipped, so the report lists every
+/// story, even the one without matching plays.
+///
+/// # Panics
+///
+/// Panics if `options` don't provide the container and the document has no body
+pub async fn run(routes: &[RouteDef], options: &RunOptions) -> TestReport {
+    let start = now();
+    let mut report = TestReport::default();
+
+    for story in stories(routes) {
+        report.stories.push(story.run(options).await);
+    }
+
+    report.duration_ms = now() - start;
+    report
+}
@@ -0,0 +1,433 @@
+//! Recording of the changes which a step made in the canvas
+//!
+//! The test viewer observes the canvas with the [MutationObserver] while the step
+//! is running, and shows the summary of the changes under the step. It helps to
+//! find out what the previous step did when the next one fails.
+
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+use leptos::wasm_bindgen::JsCast;
+use leptos::wasm_bindgen::closure::Closure;
+use leptos::web_sys::Element;
+use leptos::web_sys::HtmlElement;
+use leptos::web_sys::MutationObserver;
+use leptos::web_sys::MutationObserverInit;
+use leptos::web_sys::MutationRecord;
+use leptos::web_sys::Node;
+use leptos::web_sys::NodeList;
+use leptos::web_sys::js_sys::Array;
+
+use crate::testing::describe;
+use crate::testing::normalize_text;
+
+/// How many characters of the text are shown in the change
+const MAX_TEXT_LENGTH: usize = 60;
+
+/// Single change of the canvas DOM
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum DomChange {
+    /// Node was added to the parent element
+    Added {
+        /// description of the parent element
+        parent: String,
+        /// description of the added node
+        node: String,
+    },
+    /// Node was removed from the parent element
+    Removed {
+        /// description of the parent element
+        parent: String,
+        /// description of the removed node
+        node: String,
+    },
+    /// Attribute of the element was set, changed or removed
+    Attribute {
+        /// description of the element
+        element: String,
+        /// name of the attribute
+        name: String,
+        /// value before the step, `None` if the attribute wasn't set
+        old: Option<String>,
+        /// value after the step, `None` if the attribute was removed
+        new: Option<String>,
+    },
+    /// Text of the element was changed
+    Text {
+        /// description of the element containing the text
+        element: String,
+        /// text before the step
+        old: String,
+        /// text after the step
+        new: String,
+    },
+}
+
+/// Returns the classes added to and removed from the `class` attribute
+pub(crate) fn class_changes<'a>(old: &'a str, new: &'a str) -> (Vec<&'a str>, Vec<&'a str>) {
+    let added = new
+        .split_whitespace()
+        .filter(|class| !old.split_whitespace().any(|old| old == *class))
+        .collect();
+    let removed = old
+        .split_whitespace()
+        .filter(|class| !new.split_whitespace().any(|new| new == *class))
+        .collect();
+
+    (added, removed)
+}
+
+/// Normalizes the text and truncates it, so it fits in a single line of the diff
+pub(crate) fn shorten(text: &str) -> String {
+    let text = normalize_text(text);
+
+    match text.char_indices().nth(MAX_TEXT_LENGTH) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+/// Formats the list of classes as `` `a`, `b` ``
+fn classes(classes: &[&str]) -> String {
+    classes
+        .iter()
+        .map(|class| format!("`{class}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Display for DomChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use DomChange::*;
+        match self {
+            Added { parent, node } => write!(f, "Added `{node}` to `{parent}`"),
+            Removed { parent, node } => write!(f, "Removed `{node}` from `{parent}`"),
+            Attribute {
+                element,
+                name,
+                old,
+                new,
+            } if name == "class" => {
+                let (added, removed) = class_changes(
+                    old.as_deref().unwrap_or_default(),
+                    new.as_deref().unwrap_or_default(),
+                );
+
+                write!(f, "Changed classes of `{element}`")?;
+                if !added.is_empty() {
+                    write!(f, ", added {}", classes(&added))?;
+                }
+                if !removed.is_empty() {
+                    write!(f, ", removed {}", classes(&removed))?;
+                }
+                Ok(())
+            }
+            Attribute {
+                element,
+                name,
+                old: None,
+                new: Some(new),
+            } => {
+                write!(f, "Set `{name}` of `{element}` to `\"{new}\"`")
+            }
+            Attribute {
+                element,
+                name,
+                new: None,
+                ..
+            } => {
+                write!(f, "Removed `{name}` from `{element}`")
+            }
+            Attribute {
+                element,
+                name,
+                old,
+                new,
+            } => write!(
+                f,
+                "Changed `{name}` of `{element}` from `\"{}\"` to `\"{}\"`",
+                old.as_deref().unwrap_or_default(),
+                new.as_deref().unwrap_or_default()
+            ),
+            Text { element, old, new } => {
+                write!(
+                    f,
+                    "Changed text of `{element}` from `\"{old}\"` to `\"{new}\"`"
+                )
+            }
+        }
+    }
+}
+
+/// Changes which a step made in the canvas
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct DomDiff {
+    /// Changes in the order in which they were observed
+    pub(crate) changes: Vec<DomChange>,
+}
+
+impl DomDiff {
+    /// Returns `true` if the step didn't change the canvas
+    pub(crate) fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the diff formatted as the Markdown list
+    pub(crate) fn to_markdown(&self) -> String {
+        self.changes
+            .iter()
+            .map(|change| format!("- {change}\n"))
+            .collect()
+    }
+}
+
+/// Returns the short description of the node, or `None` if the node is not worth showing
+///
+/// Comments (which are used by the Leptos as markers) and whitespace-only text
+/// nodes are skipped.
+fn describe_node(node: &Node) -> Option<String> {
+    if let Some(element) = node.dyn_ref::<Element>() {
+        return Some(describe(element));
+    }
+
+    if node.node_type() == Node::TEXT_NODE {
+        let text = shorten(&node.text_content().unwrap_or_default());
+        return (!text.is_empty()).then(|| format!("\"{text}\""));
+    }
+
+    None
+}
+
+/// Returns the description of the parent element of the node
+fn describe_parent(node: &Node) -> String {
+    match node.dyn_ref::<Element>() {
+        Some(element) => describe(element),
+        None => node
+            .parent_element()
+            .map(|element| describe(&element))
+            .unwrap_or_else(|| node.node_name().to_lowercase()),
+    }
+}
+
+/// Returns the nodes from the list
+fn nodes(list: &NodeList) -> Vec<Node> {
+    (0..list.length())
+        .filter_map(|idx| list.item(idx))
+        .collect()
+}
+
+/// Converts the records collected by the observer into the [DomDiff]
+///
+/// Changes are merged, so every attribute and text node shows up once with its
+/// value from before and after the step. Nodes which were added and removed by the
+/// same step are skipped, as well as the changes inside of the added nodes, since
+/// the added node already covers them.
+fn diff(records: &[MutationRecord]) -> DomDiff {
+    let mut added: Vec<(Node, Node)> = Vec::new();
+    let mut removed: Vec<(Node, Node)> = Vec::new();
+    let mut attributes: Vec<(Element, String, Option<String>)> = Vec::new();
+    let mut texts: Vec<(Node, String)> = Vec::new();
+
+    for record in records {
+        let Some(target) = record.target() else {
+            continue;
+        };
+
+        match record.type_().as_str() {
+            "childList" => {
+                for node in nodes(&record.removed_nodes()) {
+                    match added.iter().position(|(added, _)| *added == node) {
+                        Some(idx) => {
+                            added.remove(idx);
+                        }
+                        None => removed.push((node, target.clone())),
+                    }
+                }
+                for node in nodes(&record.added_nodes()) {
+                    added.push((node, target.clone()));
+                }
+            }
+            "attributes" => {
+                if let Some(name) = record.attribute_name()
+                    && let Ok(element) = target.dyn_into::<Element>()
+                    && !attributes
+                        .iter()
+                        .any(|(other, other_name, _)| *other == element && *other_name == name)
+                {
+                    attributes.push((element, name, record.old_value()));
+                }
+            }
+            "characterData" => {
+                if !texts.iter().any(|(other, _)| *other == target) {
+                    texts.push((target, record.old_value().unwrap_or_default()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let inside_added = |node: &Node| added.iter().any(|(added, _)| added.contains(Some(node)));
+
+    let mut changes = Vec::new();
+
+    changes.extend(removed.iter().filter_map(|(node, parent)| {
+        Some(DomChange::Removed {
+            parent: describe_parent(parent),
+            node: describe_node(node)?,
+        })
+    }));
+
+    changes.extend(added.iter().filter_map(|(node, parent)| {
+        Some(DomChange::Added {
+            parent: describe_parent(parent),
+            node: describe_node(node)?,
+        })
+    }));
+
+    changes.extend(
+        attributes
+            .into_iter()
+            .filter(|(element, _, _)| !inside_added(element))
+            .filter_map(|(element, name, old)| {
+                let new = element.get_attribute(&name);
+                (old != new).then(|| DomChange::Attribute {
+                    element: describe(&element),
+                    name,
+                    old,
+                    new,
+                })
+            }),
+    );
+
+    changes.extend(
+        texts
+            .into_iter()
+            .filter(|(node, _)| !inside_added(node))
+            .filter_map(|(node, old)| {
+                let old = shorten(&old);
+                let new = shorten(&node.text_content().unwrap_or_default());
+                (old != new).then(|| DomChange::Text {
+                    element: describe_parent(&node),
+                    old,
+                    new,
+                })
+            }),
+    );
+
+    DomDiff { changes }
+}
+
+/// Records the mutation records of the array passed by the observer
+fn extend(records: &RefCell<Vec<MutationRecord>>, array: &Array) {
+    records.borrow_mut().extend(
+        array
+            .iter()
+            .filter_map(|record| record.dyn_into::<MutationRecord>().ok()),
+    );
+}
+
+/// Observes the changes in the canvas while the step is running
+pub(crate) struct MutationRecorder {
+    /// Observer of the canvas
+    observer: MutationObserver,
+    /// Records delivered by the observer so far
+    records: Rc<RefCell<Vec<MutationRecord>>>,
+    /// Callback of the observer, which must live as long as the observer
+    _callback: Closure<dyn FnMut(Array, MutationObserver)>,
+}
+
+impl MutationRecorder {
+    /// Starts observing the `canvas`
+    ///
+    /// Returns `None` if the browser refused to observe the canvas.
+    pub(crate) fn start(canvas: &HtmlElement) -> Option<Self> {
+        let records = Rc::new(RefCell::new(Vec::new()));
+        let callback_records = Rc::clone(&records);
+        let callback = Closure::<dyn FnMut(Array, MutationObserver)>::new(
+            move |array: Array, _observer: MutationObserver| extend(&callback_records, &array),
+        );
+
+        let observer = MutationObserver::new(callback.as_ref().unchecked_ref()).ok()?;
+
+        let options = MutationObserverInit::new();
+        options.set_subtree(true);
+        options.set_child_list(true);
+        options.set_attributes(true);
+        options.set_attribute_old_value(true);
+        options.set_character_data(true);
+        options.set_character_data_old_value(true);
+
+        observer.observe_with_options(canvas, &options).ok()?;
+
+        Some(Self {
+            observer,
+            records,
+            _callback: callback,
+        })
+    }
+
+    /// Stops observing the canvas and returns the changes made since the [start][MutationRecorder::start]
+    pub(crate) fn finish(self) -> DomDiff {
+        extend(&self.records, &self.observer.take_records());
+        self.observer.disconnect();
+
+        diff(&self.records.borrow())
+    }
+}
+
+#[cfg(test)]
+/// Tests of the DOM changes
+mod tests {
+    use super::DomChange;
+    use super::class_changes;
+    use super::shorten;
+
+    /// Added and removed classes are found regardless of their order
+    #[test]
+    fn class_changes_of_attribute() {
+        let (added, removed) = class_changes("btn hidden active", "active btn  open");
+
+        assert_eq!(added, vec!["open"]);
+        assert_eq!(removed, vec!["hidden"]);
+    }
+
+    /// Long texts are truncated in the diff
+    #[test]
+    fn shorten_long_text() {
+        assert_eq!(shorten("  short \n text "), "short text");
+        assert_eq!(shorten(&"a".repeat(100)), format!("{}…", "a".repeat(60)));
+    }
+
+    /// Changes are described in the test viewer
+    #[test]
+    fn dom_change_display() {
+        let classes = DomChange::Attribute {
+            element: "<button>".to_string(),
+            name: "class".to_string(),
+            old: Some("btn".to_string()),
+            new: Some("btn active".to_string()),
+        };
+        let removed = DomChange::Attribute {
+            element: "<button>".to_string(),
+            name: "disabled".to_string(),
+            old: Some(String::new()),
+            new: None,
+        };
+        let text = DomChange::Text {
+            element: "<span>".to_string(),
+            old: "1".to_string(),
+            new: "2".to_string(),
+        };
+
+        assert_eq!(
+            classes.to_string(),
+            "Changed classes of `<button>`, added `active`"
+        );
+        assert_eq!(removed.to_string(), "Removed `disabled` from `<button>`");
+        assert_eq!(
+            text.to_string(),
+            "Changed text of `<span>` from `\"1\"` to `\"2\"`"
+        );
+    }
+}
@@ -0,0 +1,499 @@
+//! Recording of the user interactions as the source of a play
+//!
+//! While recording, the clicks, typing, key presses and selected options in the
+//! canvas are turned into [RecordedAction]s. The actions are then written as the
+//! `play::<S>("...").next(...)` chain which can be pasted into the `plays` method
+//! of the story.
+//!
+//! Elements are found with the most stable query available: the `data-testid`
+//! set with [test_id][crate::test_id], the label of the form control, the text
+//! and finally the role of the element.
+
+use std::fmt::Display;
+
+use leptos::wasm_bindgen::JsCast;
+use leptos::wasm_bindgen::closure::Closure;
+use leptos::web_sys::Element;
+use leptos::web_sys::Event;
+use leptos::web_sys::HtmlElement;
+use leptos::web_sys::HtmlSelectElement;
+use leptos::web_sys::KeyboardEvent;
+
+use crate::testing::By;
+use crate::testing::describe;
+use crate::testing::find_all;
+use crate::testing::normalize_text;
+use crate::testing::own_text;
+use crate::testing::role;
+
+/// Keys which are recorded as the key presses, all other named keys are ignored
+const RECORDED_KEYS: [&str; 14] = [
+    "Enter",
+    "Escape",
+    "Tab",
+    "Backspace",
+    "Delete",
+    "ArrowUp",
+    "ArrowDown",
+    "ArrowLeft",
+    "ArrowRight",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    " ",
+];
+
+/// Elements which handle the user interactions themselves
+const INTERACTIVE: &str = "button, a[href], input, select, textarea, summary, [role], [tabindex]";
+
+/// Query used to find the element in the recorded play
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Selector {
+    /// Value of the `data-testid` attribute
+    TestId(String),
+    /// Label of the form control
+    Label(String),
+    /// Own text of the element, the text of its direct text nodes matched by [By::Text]
+    Text(String),
+    /// Role of the element
+    Role(String),
+    /// Element which can't be found by any query, with its description
+    Unknown(String),
+}
+
+impl Selector {
+    /// Returns the expression which finds the element in the canvas
+    ///
+    /// The element which can't be found is written as the `compile_error!`, so the
+    /// copied play doesn't compile until the element gets a test id.
+    fn to_code(&self) -> String {
+        use Selector::*;
+        match self {
+            TestId(id) => format!("ctx.get_by_test_id({id:?})?"),
+            Label(label) => format!("ctx.get_by_label_text({label:?})?"),
+            Text(text) => format!("ctx.get_by_text({text:?})?"),
+            Role(role) => format!("ctx.get_by_role({role:?})?"),
+            Unknown(element) => format!(
+                "compile_error!({:?})",
+                format!("add a test id to the {element} and find it with `ctx.get_by_test_id`")
+            ),
+        }
+    }
+}
+
+impl Display for Selector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Selector::*;
+        match self {
+            TestId(value) | Label(value) | Text(value) | Role(value) | Unknown(value) => {
+                write!(f, "`{value}`")
+            }
+        }
+    }
+}
+
+/// Single user interaction recorded in the canvas
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RecordedAction {
+    /// Click on the element
+    Click(Selector),
+    /// Text typed into the input
+    Type(Selector, String),
+    /// Key which doesn't produce a character, like `Enter`
+    PressKey(Selector, String),
+    /// Option selected in the `<select>`
+    Select(Selector, String),
+}
+
+impl RecordedAction {
+    /// Returns the selector of the element the action was performed on
+    fn selector(&self) -> &Selector {
+        use RecordedAction::*;
+        match self {
+            Click(selector) | Type(selector, _) | PressKey(selector, _) | Select(selector, _) => {
+                selector
+            }
+        }
+    }
+
+    /// Returns the description of the step performing the action
+    fn description(&self) -> String {
+        use RecordedAction::*;
+        match self {
+            Click(selector) => format!("Click the {selector}"),
+            Type(selector, text) => format!("Type `{text}` into the {selector}"),
+            PressKey(selector, key) => format!("Press `{key}` in the {selector}"),
+            Select(selector, option) => format!("Select `{option}` in the {selector}"),
+        }
+    }
+
+    /// Returns the body of the step performing the action
+    fn to_code(&self) -> String {
+        use RecordedAction::*;
+        let target = self.selector().to_code();
+        match self {
+            Click(_) => format!("click(&{target})"),
+            Type(_, text) => format!("type_text(&{target}, {text:?})"),
+            PressKey(_, key) => format!("press_key(&{target}, {key:?})"),
+            Select(_, option) => format!("select_option(&{target}, {option:?})"),
+        }
+    }
+}
+
+/// Adds the action to the recorded ones
+///
+/// Consecutive characters typed into the same input are merged into a single
+/// action. The click which focused the input or the select is dropped, because
+/// `type_text` and `select_option` click the element themselves.
+pub(crate) fn push_action(actions: &mut Vec<RecordedAction>, action: RecordedAction) {
+    use RecordedAction::*;
+
+    if let Type(selector, text) = &action
+        && let Some(Type(last, typed)) = actions.last_mut()
+        && last == selector
+    {
+        typed.push_str(text);
+        return;
+    }
+
+    if matches!(action, Type(..) | Select(..))
+        && let Some(Click(last)) = actions.last()
+        && last == action.selector()
+    {
+        actions.pop();
+    }
+
+    actions.push(action);
+}
+
+/// Returns the name of the type without its path, like `CounterStory`
+pub(crate) fn short_type_name(name: &str) -> &str {
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Writes the recorded actions as the source of the play
+///
+/// ```rust,ignore
+/// play::<CounterStory>("Recorded play")
+///     .next("Click the `+`", |ctx| {
+///         click(&ctx.get_by_text("+")?)
+///     })
+///     .into()
+/// ```
+pub(crate) fn play_code(story: &str, description: &str, actions: &[RecordedAction]) -> String {
+    let mut code = format!("play::<{}>({description:?})\n", short_type_name(story));
+
+    for action in actions {
+        code.push_str(&format!("    .next({:?}, |ctx| {{\n", action.description()));
+        code.push_str(&format!("        {}\n", action.to_code()));
+        code.push_str("    })\n");
+    }
+
+    code.push_str("    .into()");
+    code
+}
+
+/// Returns `true` if the click landed on the `<label>`
+///
+/// Browser forwards the click to the labelled control, which is recorded instead.
+fn is_label_click(target: &Element) -> bool {
+    target
+        .closest("label, button, input, select, textarea")
+        .ok()
+        .flatten()
+        .is_some_and(|element| element.tag_name().eq_ignore_ascii_case("label"))
+}
+
+/// Returns `true` if the characters typed into the element change its value
+fn accepts_text(target: &Element) -> bool {
+    match target.tag_name().to_lowercase().as_str() {
+        "textarea" => true,
+        "input" => !matches!(
+            target
+                .get_attribute("type")
+                .map(|input_type| input_type.to_lowercase())
+                .as_deref(),
+            Some(
+                "checkbox"
+                    | "radio"
+                    | "button"
+                    | "submit"
+                    | "reset"
+                    | "file"
+                    | "image"
+                    | "range"
+                    | "color"
+                    | "hidden"
+            )
+        ),
+        _ => false,
+    }
+}
+
+/// Returns `true` if the query finds exactly the `element`
+fn finds_only(canvas: &HtmlElement, by: By<'_>, element: &Element) -> bool {
+    matches!(find_all(canvas, by).as_slice(), [found] if **found == *element)
+}
+
+/// Returns `true` if the query finds exactly one element
+fn is_unique(canvas: &HtmlElement, by: By<'_>) -> bool {
+    find_all(canvas, by).len() == 1
+}
+
+/// Returns the label of the form control, which is used to find it
+fn label(element: &Element) -> Option<String> {
+    if let Some(label) = element.get_attribute("aria-label") {
+        return Some(label);
+    }
+
+    let id = element.id();
+    if !id.is_empty()
+        && let Some(document) = element.owner_document()
+        && let Ok(Some(label)) = document.query_selector(&format!("label[for=\"{id}\"]"))
+    {
+        return label.text_content();
+    }
+
+    element
+        .closest("label")
+        .ok()
+        .flatten()
+        .and_then(|label| label.text_content())
+}
+
+/// Returns the most stable selector of the element inside of the canvas
+///
+/// Interactions usually land on the children of the interactive elements, like
+/// the icon inside of the button, so the selector is built for the closest
+/// interactive ancestor of the `target`.
+pub(crate) fn selector(canvas: &HtmlElement, target: &Element) -> Selector {
+    let element = target
+        .closest(INTERACTIVE)
+        .ok()
+        .flatten()
+        .filter(|element| canvas.contains(Some(element)) && *element != **canvas)
+        .unwrap_or_else(|| target.clone());
+
+    if let Some(id) = element.get_attribute("data-testid")
+        && is_unique(canvas, By::TestId(&id))
+    {
+        return Selector::TestId(id);
+    }
+
+    if let Some(label) = label(&element).map(|label| normalize_text(&label))
+        && !label.is_empty()
+        && finds_only(canvas, By::Label(&label), &element)
+    {
+        return Selector::Label(label);
+    }
+
+    // `By::Text` matches only the own text of the element, so the text can belong
+    // to the clicked element inside of the interactive one, like the span in a button
+    for candidate in [&element, target] {
+        let text = own_text(candidate);
+        if !text.is_empty() && finds_only(canvas, By::Text(&text), candidate) {
+            return Selector::Text(text);
+        }
+    }
+
+    if let Some(role) = role(&element)
+        && finds_only(canvas, By::Role(&role), &element)
+    {
+        return Selector::Role(role);
+    }
+
+    // The closest ancestor with the test id is still better than nothing
+    if let Ok(Some(ancestor)) = element.closest("[data-testid]")
+        && canvas.contains(Some(&ancestor))
+        && let Some(id) = ancestor.get_attribute("data-testid")
+        && is_unique(canvas, By::TestId(&id))
+    {
+        return Selector::TestId(id);
+    }
+
+    Selector::Unknown(describe(&element))
+}
+
+/// Records the user interactions in the canvas until it's dropped
+///
+/// Only the events dispatched by the browser are recorded, so the plays which
+/// run at the same time don't show up in the recording.
+pub(crate) struct Recorder {
+    /// Canvas the listeners are attached to
+    canvas: HtmlElement,
+    /// Event listeners, which must live as long as they are attached
+    listeners: Vec<(&'static str, Closure<dyn Fn(Event)>)>,
+}
+
+impl Recorder {
+    /// Starts recording the interactions in the canvas
+    ///
+    /// `on_action` is called for every recorded action.
+    pub(crate) fn start(
+        canvas: &HtmlElement,
+        on_action: impl Fn(RecordedAction) + Clone + 'static,
+    ) -> Self {
+        let mut recorder = Self {
+            canvas: canvas.clone(),
+            listeners: Vec::new(),
+        };
+
+        let record = |canvas: &HtmlElement, event: &Event| {
+            if !event.is_trusted() {
+                return None;
+            }
+            let target = event.target()?.dyn_into::<Element>().ok()?;
+            let tag = target.tag_name().to_lowercase();
+            let find = || selector(canvas, &target);
+
+            match event.type_().as_str() {
+                "click" => {
+                    let ignored =
+                        matches!(tag.as_str(), "select" | "option") || is_label_click(&target);
+                    (!ignored).then(|| RecordedAction::Click(find()))
+                }
+                "keydown" => {
+                    let event = event.dyn_ref::<KeyboardEvent>()?;
+                    if event.ctrl_key() || event.meta_key() || event.alt_key() {
+                        return None;
+                    }
+
+                    let key = event.key();
+                    if key.chars().count() == 1 && accepts_text(&target) {
+                        Some(RecordedAction::Type(find(), key))
+                    } else if RECORDED_KEYS.contains(&key.as_str()) {
+                        Some(RecordedAction::PressKey(find(), key))
+                    } else {
+                        None
+                    }
+                }
+                "change" => {
+                    let select = target.dyn_ref::<HtmlSelectElement>()?;
+                    Some(RecordedAction::Select(find(), select.value()))
+                }
+                _ => None,
+            }
+        };
+
+        for name in ["click", "keydown", "change"] {
+            let canvas = canvas.clone();
+            let on_action = on_action.clone();
+            let listener = Closure::<dyn Fn(Event)>::new(move |event: Event| {
+                if let Some(action) = record(&canvas, &event) {
+                    on_action(action);
+                }
+            });
+
+            // Listeners are attached in the capture phase, so the components
+            // which stop the propagation don't hide the interactions
+            if recorder
+                .canvas
+                .add_event_listener_with_callback_and_bool(
+                    name,
+                    listener.as_ref().unchecked_ref(),
+                    true,
+                )
+                .is_ok()
+            {
+                recorder.listeners.push((name, listener));
+            }
+        }
+
+        recorder
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        for (name, listener) in self.listeners.drain(..) {
+            // If the listener can't be removed, there is nothing else we can do about it
+            let _ = self.canvas.remove_event_listener_with_callback_and_bool(
+                name,
+                listener.as_ref().unchecked_ref(),
+                true,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+/// Tests of the recorded play
+mod tests {
+    use super::RecordedAction;
+    use super::Selector;
+    use super::play_code;
+    use super::push_action;
+    use super::short_type_name;
+
+    /// Typed characters are merged and the click focusing the input is dropped
+    #[test]
+    fn recorded_actions_are_merged() {
+        let name = Selector::Label("Name".to_string());
+        let add = Selector::TestId("add".to_string());
+        let mut actions = Vec::new();
+
+        push_action(&mut actions, RecordedAction::Click(name.clone()));
+        push_action(
+            &mut actions,
+            RecordedAction::Type(name.clone(), "a".to_string()),
+        );
+        push_action(
+            &mut actions,
+            RecordedAction::Type(name.clone(), "b".to_string()),
+        );
+        push_action(&mut actions, RecordedAction::Click(add.clone()));
+        push_action(&mut actions, RecordedAction::Click(add.clone()));
+
+        assert_eq!(
+            actions,
+            vec![
+                RecordedAction::Type(name, "ab".to_string()),
+                RecordedAction::Click(add.clone()),
+                RecordedAction::Click(add),
+            ]
+        );
+    }
+
+    /// Paths and generics are removed from the story name
+    #[test]
+    fn short_type_names() {
+        assert_eq!(
+            short_type_name("site::stories::CounterStory"),
+            "CounterStory"
+        );
+        assert_eq!(short_type_name("site::Wrapper<site::Inner>"), "Wrapper");
+        assert_eq!(short_type_name("Story"), "Story");
+    }
+
+    /// Recorded actions are written as the play chain
+    #[test]
+    fn recorded_play_code() {
+        let actions = vec![
+            RecordedAction::Type(
+                Selector::Label("Name".to_string()),
+                "Joe \"Jr\"".to_string(),
+            ),
+            RecordedAction::Click(Selector::TestId("add".to_string())),
+            RecordedAction::PressKey(Selector::Unknown("<div>".to_string()), "Escape".to_string()),
+        ];
+
+        let code = play_code("site::CounterStory", "Add a person", &actions);
+
+        assert_eq!(
+            code,
+            r#"play::<CounterStory>("Add a person")
+    .next("Type `Joe \"Jr\"` into the `Name`", |ctx| {
+        type_text(&ctx.get_by_label_text("Name")?, "Joe \"Jr\"")
+    })
+    .next("Click the `add`", |ctx| {
+        click(&ctx.get_by_test_id("add")?)
+    })
+    .next("Press `Escape` in the `<div>`", |ctx| {
+        press_key(&compile_error!("add a test id to the <div> and find it with `ctx.get_by_test_id`"), "Escape")
+    })
+    .into()"#
+        );
+    }
+}
@@ -0,0 +1,438 @@
+//! Machine readable results of the test runner
+//!
+
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::StepFailure;
+
+/// Status of the step, play or story after the test run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// Everything went well
+    Passed,
+    /// Something went wrong
+    Failed,
+    /// It wasn't run
+    ///
+    /// Step is skipped when one of the previous steps has failed. Play is skipped
+    /// when it doesn't match the filter of the run.
+    Skipped,
+}
+
+/// Result of a single step
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StepReport {
+    /// Description of the step
+    pub step: String,
+    /// Status of the step
+    pub status: Status,
+    /// How long it took to run the step in milliseconds
+    pub duration_ms: f64,
+    /// Reason of the failure if the step has failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<StepFailure>,
+}
+
+/// Result of a single play
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlayReport {
+    /// Description of the play
+    pub play: String,
+    /// Status of the play
+    ///
+    /// Play has failed if any of its steps has failed or if the play itself
+    /// couldn't be run, including the failure of its setup step.
+    pub status: Status,
+    /// How long it took to run the whole play in milliseconds
+    pub duration_ms: f64,
+    /// How long it took from mounting the story to the first rendered frame of the canvas in milliseconds
+    pub render_ms: f64,
+    /// Results of the steps in the play
+    pub steps: Vec<StepReport>,
+    /// Reason of the failure which is not related to any step
+    ///
+    /// For example when the story couldn't be mounted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<StepFailure>,
+}
+
+impl PlayReport {
+    /// Creates the report of the play which wasn't run because of the filter
+    pub(crate) fn skipped(play: &str) -> Self {
+        Self {
+            play: play.to_string(),
+            status: Status::Skipped,
+            duration_ms: 0.0,
+            render_ms: 0.0,
+            steps: Vec::new(),
+            failure: None,
+        }
+    }
+
+    /// Returns the first failed step in the play
+    pub fn failed_step(&self) -> Option<&StepReport> {
+        self.steps.iter().find(|step| step.status == Status::Failed)
+    }
+
+    /// Returns the reason of the failure of the play
+    pub fn failure(&self) -> Option<&StepFailure> {
+        self.failure
+            .as_ref()
+            .or_else(|| self.failed_step().and_then(|step| step.failure.as_ref()))
+    }
+}
+
+/// Result of all plays of a single story
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StoryReport {
+    /// Path to the story in the application
+    ///
+    /// For example `/guides/adding_tests/tested_counter_story`
+    pub path: String,
+    /// Label of the story in the menu
+    pub label: String,
+    /// Results of the plays of the story
+    pub plays: Vec<PlayReport>,
+}
+
+impl StoryReport {
+    /// Status of the story
+    ///
+    /// Story has failed if any of its plays has failed
+    pub fn status(&self) -> Status {
+        if self.plays.iter().any(|play| play.status == Status::Failed) {
+            Status::Failed
+        } else if !self.plays.is_empty()
+            && self.plays.iter().all(|play| play.status == Status::Skipped)
+        {
+            Status::Skipped
+        } else {
+            Status::Passed
+        }
+    }
+
+    /// Number of plays with a given status
+    pub fn count(&self, status: Status) -> usize {
+        self.plays
+            .iter()
+            .filter(|play| play.status == status)
+            .count()
+    }
+
+    /// How long it took to run all plays of the story in milliseconds
+    pub fn duration_ms(&self) -> f64 {
+        self.plays.iter().map(|play| play.duration_ms).sum()
+    }
+}
+
+/// Result of the whole test run
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TestReport {
+    /// Results of every story which has at least one play
+    pub stories: Vec<StoryReport>,
+    /// How long it took to run all of the tests in milliseconds
+    pub duration_ms: f64,
+}
+
+impl TestReport {
+    /// Number of plays with a given status
+    pub fn count(&self, status: Status) -> usize {
+        self.stories.iter().map(|story| story.count(status)).sum()
+    }
+
+    /// Returns `true` if none of the plays has failed
+    pub fn is_success(&self) -> bool {
+        self.count(Status::Failed) == 0
+    }
+
+    /// Short, human readable summary of the test run
+    ///
+    /// For example `12 passed, 1 failed, 0 skipped`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} passed, {} failed, {} skipped",
+            self.count(Status::Passed),
+            self.count(Status::Failed),
+            self.count(Status::Skipped),
+        )
+    }
+
+    /// Exports the report as a JSON document
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the report couldn't be serialized
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Exports the report as a JUnit XML document
+    ///
+    /// Every story becomes a `<testsuite>` and every play becomes a `<testcase>`
+    /// inside of it.
+    pub fn to_junit(&self) -> String {
+        let mut xml = String::new();
+
+        // `write!` into the `String` never fails, so we can safely ignore the results
+        let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(
+            xml,
+            r#"<testsuites name="leptos_forge" tests="{}" failures="{}" skipped="{}" time="{}">"#,
+            self.stories
+                .iter()
+                .map(|story| story.plays.len())
+                .sum::<usize>(),
+            self.count(Status::Failed),
+            self.count(Status::Skipped),
+            seconds(self.duration_ms),
+        );
+
+        for story in &self.stories {
+            let _ = writeln!(
+                xml,
+                r#"  <testsuite name="{}" tests="{}" failures="{}" skipped="{}" time="{}">"#,
+                escape_xml(&story.path),
+                story.plays.len(),
+                story.count(Status::Failed),
+                story.count(Status::Skipped),
+                seconds(story.duration_ms()),
+            );
+
+            for play in &story.plays {
+                let _ = write!(
+                    xml,
+                    r#"    <testcase classname="{}" name="{}" time="{}""#,
+                    escape_xml(&story.path),
+                    escape_xml(&play.play),
+                    seconds(play.duration_ms),
+                );
+
+                match play.status {
+                    Status::Passed => {
+                        let _ = writeln!(xml, " />");
+                    }
+                    Status::Skipped => {
+                        let _ = writeln!(xml, ">");
+                        let _ = writeln!(xml, "      <skipped />");
+                        let _ = writeln!(xml, "    </testcase>");
+                    }
+                    Status::Failed => {
+                        let message = match (play.failed_step(), play.failure()) {
+                            (Some(step), Some(failure)) => {
+                                format!("Step `{}` failed: {}", step.step, failure)
+                            }
+                            (None, Some(failure)) => failure.to_string(),
+                            (_, None) => "Play has failed".to_string(),
+                        };
+                        let details = play
+                            .failure()
+                            .map(|failure| failure.to_markdown())
+                            .unwrap_or_default();
+
+                        let _ = writeln!(xml, ">");
+                        let _ = writeln!(
+                            xml,
+                            r#"      <failure message="{}">{}</failure>"#,
+                            escape_xml(&message),
+                            escape_xml(&details),
+                        );
+                        let _ = writeln!(xml, "    </testcase>");
+                    }
+                }
+            }
+
+            let _ = writeln!(xml, "  </testsuite>");
+        }
+
+        let _ = writeln!(xml, "</testsuites>");
+
+        xml
+    }
+}
+
+/// Formats the duration in milliseconds for the UI, like `12.5 ms` or `1.25 s`
+pub(crate) fn format_duration(duration_ms: f64) -> String {
+    if duration_ms < 1000.0 {
+        format!("{duration_ms:.1} ms")
+    } else {
+        format!("{:.2} s", duration_ms / 1000.0)
+    }
+}
+
+/// Formats the milliseconds as seconds as expected by the JUnit format
+fn seconds(duration_ms: f64) -> String {
+    format!("{:.3}", duration_ms / 1000.0)
+}
+
+/// Escapes the text so it can be used as an XML attribute value or text
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(char),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+/// Tests of the reports
+mod tests {
+    use super::PlayReport;
+    use super::Status;
+    use super::StepReport;
+    use super::StoryReport;
+    use super::TestReport;
+    use super::escape_xml;
+    use super::format_duration;
+    use crate::StepFailure;
+
+    /// Creates a report for the play with two steps
+    fn play_report(play: &str, failure: Option<StepFailure>) -> PlayReport {
+        let failed = failure.is_some();
+
+        PlayReport {
+            play: play.to_string(),
+            status: if failed {
+                Status::Failed
+            } else {
+                Status::Passed
+            },
+            duration_ms: 1500.0,
+            render_ms: 12.5,
+            steps: vec![
+                StepReport {
+                    step: "First step".to_string(),
+                    status: if failed {
+                        Status::Failed
+                    } else {
+                        Status::Passed
+                    },
+                    duration_ms: 1000.0,
+                    failure,
+                },
+                StepReport {
+                    step: "Second step".to_string(),
+                    status: if failed {
+                        Status::Skipped
+                    } else {
+                        Status::Passed
+                    },
+                    duration_ms: 500.0,
+                    failure: None,
+                },
+            ],
+            failure: None,
+        }
+    }
+
+    /// Creates a report with one passing and one failing play
+    fn test_report() -> TestReport {
+        TestReport {
+            stories: vec![StoryReport {
+                path: "/components/button".to_string(),
+                label: "Button".to_string(),
+                plays: vec![
+                    play_report("Button can be clicked", None),
+                    play_report(
+                        "Button <shows> the label",
+                        Some(StepFailure::mismatch("Wrong label", "Ok", "Cancel")),
+                    ),
+                ],
+            }],
+            duration_ms: 3000.0,
+        }
+    }
+
+    /// Special characters are escaped
+    #[test]
+    fn escape_special_characters() {
+        assert_eq!(
+            escape_xml(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &apos;Jerry&apos;&lt;/a&gt;"
+        );
+    }
+
+    /// Plays are counted by their status
+    #[test]
+    fn report_counts() {
+        let report = test_report();
+
+        assert_eq!(report.count(Status::Passed), 1);
+        assert_eq!(report.count(Status::Failed), 1);
+        assert_eq!(report.count(Status::Skipped), 0);
+        assert!(!report.is_success());
+        assert_eq!(report.summary(), "1 passed, 1 failed, 0 skipped");
+        assert_eq!(report.stories[0].status(), Status::Failed);
+    }
+
+    /// JUnit report contains a test case per play and the failure reason
+    #[test]
+    fn report_junit() {
+        let junit = test_report().to_junit();
+
+        assert!(junit.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(junit.contains(
+            r#"<testsuites name="leptos_forge" tests="2" failures="1" skipped="0" time="3.000">"#
+        ));
+        assert!(junit.contains(
+            r#"<testcase classname="/components/button" name="Button can be clicked" time="1.500" />"#
+        ));
+        assert!(junit.contains(r#"name="Button &lt;shows&gt; the label""#));
+        assert!(junit.contains(
+            r#"<failure message="Step `First step` failed: Wrong label (expected: Ok, actual: Cancel)">"#
+        ));
+    }
+
+    /// JSON report uses lowercase statuses and skips missing failures
+    #[test]
+    fn report_json() {
+        let json = test_report().to_json().unwrap();
+
+        assert!(json.contains(r#""status": "failed""#));
+        assert!(json.contains(r#""expected": "Ok""#));
+        assert!(!json.contains(r#""details""#));
+        assert!(json.contains(r#""render_ms": 12.5"#));
+    }
+
+    /// Short durations are shown in milliseconds and long ones in seconds
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(12.34), "12.3 ms");
+        assert_eq!(format_duration(999.9), "999.9 ms");
+        assert_eq!(format_duration(1250.0), "1.25 s");
+    }
+
+    /// Play whose setup has failed is a failure, only the plays left out by the filter are skipped
+    #[test]
+    fn report_skipped_junit() {
+        let setup_failed = play_report("Setup failed", Some("No data".into()));
+
+        let report = TestReport {
+            stories: vec![StoryReport {
+                path: "/components/button".to_string(),
+                label: "Button".to_string(),
+                plays: vec![PlayReport::skipped("Filtered out"), setup_failed],
+            }],
+            duration_ms: 0.0,
+        };
+        let junit = report.to_junit();
+
+        assert_eq!(report.count(Status::Skipped), 1);
+        assert_eq!(report.count(Status::Failed), 1);
+        assert!(!report.is_success());
+        assert_eq!(report.stories[0].status(), Status::Failed);
+        assert!(junit.contains("      <skipped />"));
+        assert!(junit.contains(r#"<failure message="Step `First step` failed: No data">"#));
+    }
+}
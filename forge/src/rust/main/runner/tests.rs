@@ -0,0 +1,230 @@
+//! Tests of the story paths, the step schedule and the run of the story which don't need a browser
+//!
+
+use std::pin::pin;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+
+use leptos::wasm_bindgen::JsCast;
+use leptos::wasm_bindgen::JsValue;
+use leptos::web_sys::HtmlElement;
+
+use super::PlayFilter;
+use super::PlayFuture;
+use super::PlayInfo;
+use super::RunOptions;
+use super::Status;
+use super::StoryEntry;
+use super::TestSuite;
+use super::join_path;
+use super::schedule;
+use crate::Play;
+use crate::SimplePlay;
+use crate::SimpleStep;
+use crate::Step;
+use crate::StepContext;
+use crate::Story;
+use crate::play;
+use crate::story::Scratch;
+
+/// Root segment doesn't contribute to the path
+#[test]
+fn join_root_path() {
+    assert_eq!(join_path("", "/"), "");
+    assert_eq!(join_path("/guides", "/"), "/guides");
+}
+
+/// Segments are joined with `/`
+#[test]
+fn join_nested_path() {
+    assert_eq!(join_path("", "guides"), "/guides");
+    assert_eq!(join_path("/guides", "adding_tests"), "/guides/adding_tests");
+}
+
+/// Story with the setup and teardown steps run around every play
+#[derive(Debug, Default, Clone, Copy)]
+struct ScheduledStory;
+
+impl Story for ScheduledStory {
+    fn before_each(&self) -> Option<Box<dyn Step<Story = Self>>> {
+        Some(SimpleStep::new("Before each", |_ctx| Ok(())).into())
+    }
+
+    fn after_each(&self) -> Option<Box<dyn Step<Story = Self>>> {
+        Some(SimpleStep::new("After each", |_ctx| Ok(())).into())
+    }
+}
+
+/// Play with the setup, two steps and the teardown
+fn scheduled_play() -> SimplePlay<ScheduledStory> {
+    play::<ScheduledStory>("Scheduled play")
+        .before("Before", |_ctx| Ok(()))
+        .next("First step", |_ctx| Ok(()))
+        .next("Second step", |_ctx| Ok(()))
+        .after("After", |_ctx| Ok(()))
+}
+
+/// Returns the descriptions of the steps which are run when the `failing` step fails
+fn run_steps(failing: Option<&str>) -> Vec<&'static str> {
+    let play = scheduled_play();
+    let mut failed = false;
+    let mut run = Vec::new();
+
+    for scheduled in schedule(&ScheduledStory, &play as &dyn Play<Story = ScheduledStory>) {
+        if scheduled.is_skipped(failed) {
+            continue;
+        }
+
+        let description = scheduled.step.description();
+        run.push(description);
+        failed |= failing == Some(description);
+    }
+
+    run
+}
+
+/// Setup and teardown steps of the story and the play surround the steps of the play
+#[test]
+fn schedule_order() {
+    let play = scheduled_play();
+    let steps = schedule(&ScheduledStory, &play as &dyn Play<Story = ScheduledStory>);
+
+    let order = steps
+        .iter()
+        .map(|scheduled| (scheduled.step.description(), scheduled.teardown))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        order,
+        vec![
+            ("Before each", false),
+            ("Before", false),
+            ("First step", false),
+            ("Second step", false),
+            ("After", true),
+            ("After each", true),
+        ]
+    );
+    assert_eq!(
+        run_steps(None),
+        order.iter().map(|(step, _)| *step).collect::<Vec<_>>()
+    );
+}
+
+/// Teardown steps are still run after the failed setup or step, the other steps are skipped
+#[test]
+fn teardown_after_failure() {
+    assert_eq!(
+        run_steps(Some("Before each")),
+        vec!["Before each", "After", "After each"]
+    );
+    assert_eq!(
+        run_steps(Some("Before")),
+        vec!["Before each", "Before", "After", "After each"]
+    );
+    assert_eq!(
+        run_steps(Some("First step")),
+        vec!["Before each", "Before", "First step", "After", "After each"]
+    );
+    assert_eq!(
+        run_steps(Some("After")),
+        vec![
+            "Before each",
+            "Before",
+            "First step",
+            "Second step",
+            "After",
+            "After each"
+        ]
+    );
+}
+
+/// Suite whose plays are never run
+struct FilteredSuite;
+
+impl TestSuite for FilteredSuite {
+    fn plays(&self) -> Vec<PlayInfo> {
+        ["Button can be clicked", "Button can be focused"]
+            .into_iter()
+            .map(|description| PlayInfo {
+                description,
+                steps: vec!["Click the button"],
+                tags: vec!["mouse"],
+            })
+            .collect()
+    }
+
+    fn run_play<'a>(&'a self, _play: usize, _container: &'a HtmlElement) -> PlayFuture<'a> {
+        unreachable!("Plays which don't match the filter must not be run")
+    }
+}
+
+/// Story without the plays matching the filter is reported with all of its plays skipped
+#[test]
+fn story_without_matching_plays() {
+    let story = StoryEntry {
+        path: "/components/button".to_string(),
+        label: "Button",
+        suite: Box::new(FilteredSuite),
+    };
+    let options = RunOptions {
+        // Nothing is mounted, so the container doesn't need a browser
+        container: Some(JsValue::NULL.unchecked_into::<HtmlElement>()),
+        filter: PlayFilter::parse("#keyboard"),
+    };
+
+    let mut context = Context::from_waker(Waker::noop());
+    let Poll::Ready(report) = pin!(story.run(&options)).poll(&mut context) else {
+        panic!("Story without matching plays must not wait for anything");
+    };
+
+    assert_eq!(report.plays.len(), 2);
+    assert_eq!(report.count(Status::Skipped), 2);
+    assert_eq!(report.status(), Status::Skipped);
+}
+
+/// Story changed by the steps of the play
+#[derive(Debug, Default, Clone, Copy)]
+struct ChangedStory {
+    /// Value set by the asynchronous step
+    value: usize,
+}
+
+impl Story for ChangedStory {}
+
+/// Changes of the story and the stored values made by the asynchronous step reach the next step
+#[test]
+fn async_step_changes() {
+    let play = play::<ChangedStory>("Asynchronous step changes the story")
+        .next_async("Change the story", async |ctx| {
+            ctx.story_mut().value = 3;
+            ctx.set("stored", 5usize);
+            Ok(())
+        })
+        .next("Check the changes", |ctx| {
+            let stored: usize = ctx.get("stored")?;
+            if ctx.story().value == 3 && stored == 5 {
+                Ok(())
+            } else {
+                Err("Changes of the asynchronous step were lost".into())
+            }
+        });
+
+    // Steps don't touch the canvas, so it doesn't need a browser
+    let canvas = JsValue::NULL.unchecked_into::<HtmlElement>();
+    let mut ctx = StepContext::with_scratch(canvas, ChangedStory::default(), Scratch::default());
+    let mut context = Context::from_waker(Waker::noop());
+
+    for step in Play::steps(&play) {
+        let result = pin!(step.run_async(&mut ctx)).poll(&mut context);
+        assert_eq!(result, Poll::Ready(Ok(())), "{}", step.description());
+    }
+    assert_eq!(ctx.story().value, 3);
+
+    // Asynchronous step which doesn't wait for anything can be run synchronously too
+    *ctx.story_mut() = ChangedStory::default();
+    for step in Play::steps(&play) {
+        assert_eq!(step.run(&mut ctx), Ok(()), "{}", step.description());
+    }
+}
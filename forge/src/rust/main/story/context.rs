@@ -0,0 +1,339 @@
+//! Context in which the steps of the play are run
+//!
+//! Steps receive the [StepContext] which gives them the canvas, the story, the
+//! logger, the clock and the queries. Steps are ephemeral, so the context is also
+//! the place where they keep the values for the later steps of the same play.
+
+use std::any::Any;
+use std::any::type_name;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::rc::Rc;
+use std::time::Duration;
+
+use leptos::logging::log;
+use leptos::web_sys::HtmlElement;
+
+use super::Sleep;
+use super::StepFailure;
+use super::Story;
+use super::now;
+use super::sleep;
+use crate::testing::By;
+use crate::testing::get;
+use crate::testing::get_by_label_text;
+use crate::testing::get_by_role;
+use crate::testing::get_by_test_id;
+use crate::testing::get_by_text;
+use crate::testing::query;
+
+/// Value stored by one of the steps
+struct ScratchValue {
+    /// The value itself
+    value: Box<dyn Any>,
+    /// Name of the type of the value, shown when it's read as a different type
+    type_name: &'static str,
+}
+
+/// Values stored by the steps of the play, shared by all of the steps
+#[derive(Default)]
+struct ScratchState {
+    /// Stored values by their keys
+    values: HashMap<&'static str, ScratchValue>,
+    /// When the play has started, measured with [now]
+    started: f64,
+}
+
+/// Storage of the values passed between the steps of the same play
+///
+/// It's cheap to clone, every clone shares the same values.
+#[derive(Clone, Default)]
+pub(crate) struct Scratch(Rc<RefCell<ScratchState>>);
+
+impl Scratch {
+    /// Creates an empty storage for the play which has started at `started`
+    pub(crate) fn new(started: f64) -> Self {
+        Self(Rc::new(RefCell::new(ScratchState {
+            values: HashMap::new(),
+            started,
+        })))
+    }
+
+    /// Stores the `value` under the `key`, replacing the previous one
+    pub(crate) fn set<T: 'static>(&self, key: &'static str, value: T) {
+        let value = ScratchValue {
+            value: Box::new(value),
+            type_name: type_name::<T>(),
+        };
+
+        self.0.borrow_mut().values.insert(key, value);
+    }
+
+    /// Returns the copy of the value stored under the `key`
+    pub(crate) fn get<T: Clone + 'static>(&self, key: &'static str) -> Result<T, StepFailure> {
+        let state = self.0.borrow();
+        let stored = state.values.get(key).ok_or_else(|| missing(key))?;
+
+        stored
+            .value
+            .downcast_ref::<T>()
+            .cloned()
+            .ok_or_else(|| wrong_type::<T>(key, stored.type_name))
+    }
+
+    /// Removes the value stored under the `key` and returns it
+    pub(crate) fn take<T: 'static>(&self, key: &'static str) -> Result<T, StepFailure> {
+        let mut state = self.0.borrow_mut();
+        let stored = state.values.remove(key).ok_or_else(|| missing(key))?;
+
+        match stored.value.downcast::<T>() {
+            Ok(value) => Ok(*value),
+            Err(value) => {
+                let failure = wrong_type::<T>(key, stored.type_name);
+                state.values.insert(
+                    key,
+                    ScratchValue {
+                        value,
+                        type_name: stored.type_name,
+                    },
+                );
+                Err(failure)
+            }
+        }
+    }
+
+    /// Returns `true` if there is a value stored under the `key`
+    pub(crate) fn contains(&self, key: &str) -> bool {
+        self.0.borrow().values.contains_key(key)
+    }
+
+    /// When the play has started, measured with [now]
+    fn started(&self) -> f64 {
+        self.0.borrow().started
+    }
+}
+
+/// Failure of reading the value which wasn't stored
+fn missing(key: &str) -> StepFailure {
+    StepFailure::new(format!(
+        "Value `{key}` wasn't stored by any of the previous steps"
+    ))
+}
+
+/// Failure of reading the value as a different type than it was stored
+fn wrong_type<T>(key: &str, stored: &'static str) -> StepFailure {
+    StepFailure::mismatch(
+        format!("Value `{key}` was stored with a different type"),
+        type_name::<T>(),
+        stored,
+    )
+}
+
+/// Everything the step needs to play
+///
+/// The context is created by the test runner for every play. Steps use it to
+/// reach the canvas in which the story is drawn and the story itself, to find the
+/// elements, to log the messages shown next to the step in the test viewer and to
+/// pass the values to the later steps of the same play.
+///
+/// ```rust,ignore
+/// play::<TodoStory>("Added item is counted")
+///     .next("Remember the number of items", |ctx| {
+///         let count = query_all(ctx.canvas(), By::Role("listitem")).len();
+///         ctx.set("count", count);
+///         Ok(())
+///     })
+///     .next("Add the item", |ctx| {
+///         type_text(&ctx.get_by_label_text("New item")?, "Milk")?;
+///         click(&ctx.get_by_text("Add")?)
+///     })
+///     .next("Check the number of items", |ctx| {
+///         let count: usize = ctx.get("count")?;
+///         let items = query_all(ctx.canvas(), By::Role("listitem")).len();
+///         assert_equal("Wrong number of items", count + 1, items)
+///     })
+/// ```
+///
+/// Asynchronous steps borrow the context for as long as they run, so their changes
+/// of the story and the stored values are seen by the next steps.
+#[derive(Clone)]
+pub struct StepContext<S: Story> {
+    /// Element in which the story is drawn
+    canvas: HtmlElement,
+    /// Story being played
+    story: S,
+    /// Values passed between the steps
+    scratch: Scratch,
+}
+
+impl<S: Story> StepContext<S> {
+    /// Creates the context for the new play of the `story` drawn in the `canvas`
+    pub(crate) fn new(canvas: HtmlElement, story: S) -> Self {
+        Self::with_scratch(canvas, story, Scratch::new(now()))
+    }
+
+    /// Creates the context sharing the stored values with the other steps of the play
+    pub(crate) fn with_scratch(canvas: HtmlElement, story: S, scratch: Scratch) -> Self {
+        Self {
+            canvas,
+            story,
+            scratch,
+        }
+    }
+
+    /// Element in which the story is drawn
+    pub fn canvas(&self) -> &HtmlElement {
+        &self.canvas
+    }
+
+    /// Story being played
+    pub fn story(&self) -> &S {
+        &self.story
+    }
+
+    /// Mutable access to the story being played
+    pub fn story_mut(&mut self) -> &mut S {
+        &mut self.story
+    }
+
+    /// Stores the `value` under the `key` for the later steps of the play
+    ///
+    /// The previous value stored under the same `key` is replaced.
+    pub fn set<T: 'static>(&mut self, key: &'static str, value: T) {
+        self.scratch.set(key, value);
+    }
+
+    /// Returns the copy of the value stored under the `key` by one of the previous steps
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no value under the `key` or if it was stored with a different type.
+    pub fn get<T: Clone + 'static>(&self, key: &'static str) -> Result<T, StepFailure> {
+        self.scratch.get(key)
+    }
+
+    /// Removes the value stored under the `key` by one of the previous steps and returns it
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no value under the `key` or if it was stored with a different type.
+    pub fn take<T: 'static>(&mut self, key: &'static str) -> Result<T, StepFailure> {
+        self.scratch.take(key)
+    }
+
+    /// Returns `true` if one of the previous steps has stored a value under the `key`
+    pub fn contains(&self, key: &str) -> bool {
+        self.scratch.contains(key)
+    }
+
+    /// Writes the `message` to the console
+    ///
+    /// Messages logged while the step is running are shown next to the step in
+    /// the test viewer.
+    pub fn log(&self, message: impl Display) {
+        log!("{message}");
+    }
+
+    /// Time elapsed since the play has started
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs_f64((now() - self.scratch.started()).max(0.0) / 1000.0)
+    }
+
+    /// Waits for the given `duration` without blocking the browser, see [sleep]
+    pub fn sleep(&self, duration: Duration) -> Sleep {
+        sleep(duration)
+    }
+
+    /// Returns the element in the canvas matching the query, see [get]
+    ///
+    /// # Errors
+    ///
+    /// Fails if there isn't exactly one matching element.
+    pub fn get_element(&self, by: By<'_>) -> Result<HtmlElement, StepFailure> {
+        get(&self.canvas, by)
+    }
+
+    /// Returns the element in the canvas matching the query or `None`, see [query]
+    ///
+    /// # Errors
+    ///
+    /// Fails if more than one element matches the query.
+    pub fn query(&self, by: By<'_>) -> Result<Option<HtmlElement>, StepFailure> {
+        query(&self.canvas, by)
+    }
+
+    /// Returns the element in the canvas with the given test id
+    ///
+    /// # Errors
+    ///
+    /// Fails if there isn't exactly one matching element.
+    pub fn get_by_test_id(&self, test_id: &str) -> Result<HtmlElement, StepFailure> {
+        get_by_test_id(&self.canvas, test_id)
+    }
+
+    /// Returns the element in the canvas with the given role
+    ///
+    /// # Errors
+    ///
+    /// Fails if there isn't exactly one matching element.
+    pub fn get_by_role(&self, role: &str) -> Result<HtmlElement, StepFailure> {
+        get_by_role(&self.canvas, role)
+    }
+
+    /// Returns the form control in the canvas with the given label
+    ///
+    /// # Errors
+    ///
+    /// Fails if there isn't exactly one matching element.
+    pub fn get_by_label_text(&self, label: &str) -> Result<HtmlElement, StepFailure> {
+        get_by_label_text(&self.canvas, label)
+    }
+
+    /// Returns the element in the canvas with the given text
+    ///
+    /// # Errors
+    ///
+    /// Fails if there isn't exactly one matching element.
+    pub fn get_by_text(&self, text: &str) -> Result<HtmlElement, StepFailure> {
+        get_by_text(&self.canvas, text)
+    }
+}
+
+#[cfg(test)]
+/// Tests of the values passed between the steps
+mod tests {
+    use super::Scratch;
+
+    /// Stored value can be read by the later steps and the clones share the values
+    #[test]
+    fn stored_values() {
+        let scratch = Scratch::new(0.0);
+        let clone = scratch.clone();
+
+        scratch.set("count", 3usize);
+
+        assert_eq!(clone.get::<usize>("count").unwrap(), 3);
+        assert!(clone.contains("count"));
+        assert_eq!(clone.take::<usize>("count").unwrap(), 3);
+        assert!(!scratch.contains("count"));
+    }
+
+    /// Missing values and values of a different type fail the step
+    #[test]
+    fn wrong_values() {
+        let scratch = Scratch::new(0.0);
+        scratch.set("name", "Joe".to_string());
+
+        let missing = scratch.get::<usize>("count").unwrap_err();
+        let wrong = scratch.take::<usize>("name").unwrap_err();
+
+        assert_eq!(
+            missing.message(),
+            "Value `count` wasn't stored by any of the previous steps"
+        );
+        assert_eq!(wrong.expected(), Some("usize"));
+        assert_eq!(wrong.actual(), Some("alloc::string::String"));
+        assert!(scratch.contains("name"));
+    }
+}
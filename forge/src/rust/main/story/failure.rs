@@ -0,0 +1,327 @@
+//! Describes why the [Step][super::Step] has failed
+//!
+
+use std::error::Error;
+use std::fmt::Display;
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// Result of running a [Step][super::Step]
+pub type StepResult = Result<(), StepFailure>;
+
+/// Failure of the [Step][super::Step]
+///
+/// Besides the message, failure can carry the expected and actual values and
+/// a longer description of the problem formatted in Markdown. The test viewer
+/// renders all of it below the failed step.
+///
+/// ```rust
+/// # use leptos_forge::StepFailure;
+///
+/// let failure = StepFailure::new("Counter has a wrong value")
+///     .with_expected(3)
+///     .with_actual(5)
+///     .with_details("Counter should be increased **once** per click");
+///
+/// assert_eq!(failure.message(), "Counter has a wrong value");
+/// assert_eq!(failure.expected(), Some("3"));
+/// assert_eq!(failure.actual(), Some("5"));
+/// ```
+///
+/// In most cases you can just convert the message into the failure
+///
+/// ```rust
+/// # use leptos_forge::StepResult;
+///
+/// fn step() -> StepResult {
+///     Err("Label should be present".into())
+/// }
+///
+/// assert!(step().is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StepFailure {
+    /// Short description of the failure
+    message: String,
+    /// Value which step expected to find
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected: Option<String>,
+    /// Value which step has found
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actual: Option<String>,
+    /// Longer description of the failure formatted in Markdown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<String>,
+}
+
+impl StepFailure {
+    /// Create new instance of the failure with the given message
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        Self {
+            message: message.into(),
+            expected: None,
+            actual: None,
+            details: None,
+        }
+    }
+
+    /// Create new instance of the failure for the step which didn't pass before the `timeout` expired
+    ///
+    /// If the step has failed before the timeout, the `last` failure is kept, so
+    /// the UI can show why the step didn't pass.
+    pub fn timed_out(timeout: Duration, last: Option<StepFailure>) -> Self {
+        let message = format!("Timed out after {} ms", timeout.as_millis());
+
+        match last {
+            Some(last) => Self {
+                message: format!("{message}: {}", last.message),
+                ..last
+            },
+            None => Self::new(message),
+        }
+    }
+
+    /// Create new instance of the failure for the step which took longer than its `budget`
+    ///
+    /// `duration_ms` is how long the step took in milliseconds.
+    pub fn over_budget(budget: Duration, duration_ms: f64) -> Self {
+        Self::mismatch(
+            format!(
+                "Step took longer than its budget of {} ms",
+                budget.as_millis()
+            ),
+            format!("at most {} ms", budget.as_millis()),
+            format!("{duration_ms:.1} ms"),
+        )
+    }
+
+    /// Create new instance of the failure for the case when the value doesn't match the expectations
+    pub fn mismatch<S: Into<String>, E: ToString, A: ToString>(
+        message: S,
+        expected: E,
+        actual: A,
+    ) -> Self {
+        Self::new(message)
+            .with_expected(expected)
+            .with_actual(actual)
+    }
+
+    /// Sets the value which the step expected to find
+    pub fn with_expected<E: ToString>(mut self, expected: E) -> Self {
+        self.expected = Some(expected.to_string());
+        self
+    }
+
+    /// Sets the value which the step has found
+    pub fn with_actual<A: ToString>(mut self, actual: A) -> Self {
+        self.actual = Some(actual.to_string());
+        self
+    }
+
+    /// Sets the longer description of the failure formatted in Markdown
+    pub fn with_details<D: Into<String>>(mut self, details: D) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    /// Short description of the failure
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Value which the step expected to find
+    pub fn expected(&self) -> Option<&str> {
+        self.expected.as_deref()
+    }
+
+    /// Value which the step has found
+    pub fn actual(&self) -> Option<&str> {
+        self.actual.as_deref()
+    }
+
+    /// Longer description of the failure formatted in Markdown
+    pub fn details(&self) -> Option<&str> {
+        self.details.as_deref()
+    }
+
+    /// Formats the failure as Markdown so it can be shown in the UI
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = self.message.clone();
+        let has_values = self.expected.is_some() || self.actual.is_some();
+
+        if has_values {
+            markdown.push_str("\n\n");
+        }
+
+        if let Some(expected) = &self.expected {
+            markdown.push_str("- **Expected:** ");
+            markdown.push_str(&code_span(expected));
+            markdown.push('\n');
+        }
+
+        if let Some(actual) = &self.actual {
+            markdown.push_str("- **Actual:** ");
+            markdown.push_str(&code_span(actual));
+            markdown.push('\n');
+        }
+
+        if let Some(details) = &self.details {
+            // The list of values already ends with the line break
+            markdown.push_str(if has_values { "\n" } else { "\n\n" });
+            markdown.push_str(details);
+        }
+
+        markdown
+    }
+}
+
+/// Wraps the value into the Markdown code span
+///
+/// If value contains backticks, we use long enough backtick sequence so the
+/// value is shown verbatim
+fn code_span(value: &str) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+
+    for char in value.chars() {
+        if char == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+
+    if longest == 0 {
+        format!("`{value}`")
+    } else {
+        let fence = "`".repeat(longest + 1);
+        format!("{fence} {value} {fence}")
+    }
+}
+
+impl Display for StepFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)?;
+
+        if let Some(expected) = &self.expected {
+            write!(f, " (expected: {expected}")?;
+            if let Some(actual) = &self.actual {
+                write!(f, ", actual: {actual}")?;
+            }
+            f.write_str(")")?;
+        } else if let Some(actual) = &self.actual {
+            write!(f, " (actual: {actual})")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for StepFailure {}
+
+impl From<&str> for StepFailure {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<String> for StepFailure {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+#[cfg(test)]
+/// Tests of the [StepFailure] formatting
+mod tests {
+    use std::time::Duration;
+
+    use super::StepFailure;
+    use super::code_span;
+
+    /// Timeout keeps the values of the last failure
+    #[test]
+    fn timed_out_keeps_last_failure() {
+        let last = StepFailure::mismatch("Wrong value", 3, 5);
+        let failure = StepFailure::timed_out(Duration::from_millis(250), Some(last));
+
+        assert_eq!(failure.message(), "Timed out after 250 ms: Wrong value");
+        assert_eq!(failure.expected(), Some("3"));
+        assert_eq!(failure.actual(), Some("5"));
+    }
+
+    /// Timeout without the last failure has only the message
+    #[test]
+    fn timed_out_without_last_failure() {
+        let failure = StepFailure::timed_out(Duration::from_secs(1), None);
+
+        assert_eq!(failure, StepFailure::new("Timed out after 1000 ms"));
+    }
+
+    /// Budget and the real duration are kept as the expected and actual values
+    #[test]
+    fn over_budget() {
+        let failure = StepFailure::over_budget(Duration::from_millis(50), 72.44);
+
+        assert_eq!(
+            failure.message(),
+            "Step took longer than its budget of 50 ms"
+        );
+        assert_eq!(failure.expected(), Some("at most 50 ms"));
+        assert_eq!(failure.actual(), Some("72.4 ms"));
+    }
+
+    /// Failure with just a message is rendered as the message
+    #[test]
+    fn markdown_message_only() {
+        let failure = StepFailure::new("Button is missing");
+
+        assert_eq!(failure.to_markdown(), "Button is missing");
+    }
+
+    /// Expected and actual values are rendered as a list of code spans
+    #[test]
+    fn markdown_mismatch() {
+        let failure = StepFailure::mismatch("Wrong value", 3, 5);
+
+        assert_eq!(
+            failure.to_markdown(),
+            "Wrong value\n\n- **Expected:** `3`\n- **Actual:** `5`\n"
+        );
+    }
+
+    /// Details are appended after the values, separated by a single blank line
+    #[test]
+    fn markdown_details() {
+        let failure = StepFailure::new("Wrong value")
+            .with_actual("abc")
+            .with_details("More *details*");
+        let without_values = StepFailure::new("Wrong value").with_details("More *details*");
+
+        assert_eq!(
+            failure.to_markdown(),
+            "Wrong value\n\n- **Actual:** `abc`\n\nMore *details*"
+        );
+        assert_eq!(
+            without_values.to_markdown(),
+            "Wrong value\n\nMore *details*"
+        );
+    }
+
+    /// Backticks in the value are escaped using longer fence
+    #[test]
+    fn code_span_with_backticks() {
+        assert_eq!(code_span("a`b"), "`` a`b ``");
+        assert_eq!(code_span("a``b"), "``` a``b ```");
+    }
+
+    /// Display shows the values inline
+    #[test]
+    fn display_mismatch() {
+        let failure = StepFailure::mismatch("Wrong value", 3, 5);
+
+        assert_eq!(failure.to_string(), "Wrong value (expected: 3, actual: 5)");
+    }
+}
@@ -0,0 +1,208 @@
+//! Declarative syntax for writing the plays
+//!
+
+/// Creates a play from the list of steps
+///
+/// It's a shorter way of writing the [SimplePlay][crate::SimplePlay] builder
+/// chain. The play starts with the type of the story and the description of the
+/// play, followed by the steps, each of them ending with a semicolon
+///
+/// - `before "description" => closure;` - setup step of the play
+/// - `step "description" => closure;` - next step of the play
+/// - `step async "description" => async closure;` - next asynchronous step of the play
+/// - `reuse step;` - shared [SimpleStep][crate::SimpleStep]
+/// - `include play;` - all steps of the sub-play
+/// - `timeout duration;` - timeout of the last added step
+/// - `budget duration;` - performance budget of the last added step
+/// - `tag "tag";` - tag of the play
+/// - `after "description" => closure;` - teardown step of the play
+///
+/// Steps are closures, so they can capture the values from their surroundings.
+///
+/// ```rust,ignore
+/// use leptos_forge::play;
+/// use leptos_forge::testing::*;
+///
+/// fn increase(times: usize) -> SimplePlay<CounterStory> {
+///     play! { CounterStory: "Increase the counter";
+///         step "Click the + button" => move |ctx| {
+///             for _ in 0..times {
+///                 click(&ctx.get_by_text("+")?)?;
+///             }
+///             Ok(())
+///         };
+///     }
+/// }
+///
+/// let message: Box<dyn Play<Story = CounterStory>> = play! { CounterStory: "Message is shown above the threshold";
+///     tag "smoke";
+///     before "Set the threshold" => |ctx| {
+///         ctx.story().threshold.set(2);
+///         Ok(())
+///     };
+///     include increase(3);
+///     step "Check the message" => |ctx| {
+///         assert_text(&ctx.get_by_test_id("message")?, "Value has crossed the threshold")
+///     };
+///     timeout Duration::from_millis(500);
+/// }
+/// .into();
+/// ```
+#[macro_export]
+macro_rules! play {
+    (@steps $play:expr;) => {
+        $play
+    };
+    (@steps $play:expr; before $description:expr => $step:expr; $($rest:tt)*) => {
+        $crate::play!(@steps $play.before($description, $step); $($rest)*)
+    };
+    (@steps $play:expr; after $description:expr => $step:expr; $($rest:tt)*) => {
+        $crate::play!(@steps $play.after($description, $step); $($rest)*)
+    };
+    (@steps $play:expr; step async $description:expr => $step:expr; $($rest:tt)*) => {
+        $crate::play!(@steps $play.next_async($description, $step); $($rest)*)
+    };
+    (@steps $play:expr; step $description:expr => $step:expr; $($rest:tt)*) => {
+        $crate::play!(@steps $play.next($description, $step); $($rest)*)
+    };
+    (@steps $play:expr; reuse $step:expr; $($rest:tt)*) => {
+        $crate::play!(@steps $play.step($step); $($rest)*)
+    };
+    (@steps $play:expr; include $sub_play:expr; $($rest:tt)*) => {
+        $crate::play!(@steps $play.include($sub_play); $($rest)*)
+    };
+    (@steps $play:expr; timeout $timeout:expr; $($rest:tt)*) => {
+        $crate::play!(@steps $play.timeout($timeout); $($rest)*)
+    };
+    (@steps $play:expr; budget $budget:expr; $($rest:tt)*) => {
+        $crate::play!(@steps $play.budget($budget); $($rest)*)
+    };
+    (@steps $play:expr; tag $tag:expr; $($rest:tt)*) => {
+        $crate::play!(@steps $play.tag($tag); $($rest)*)
+    };
+
+    ($story:ty : $description:expr; $($steps:tt)*) => {
+        $crate::play!(@steps $crate::play::<$story>($description); $($steps)*)
+    };
+}
+
+#[cfg(test)]
+/// Tests of the plays created with the [play!] macro and the sub-plays
+mod tests {
+    use std::time::Duration;
+
+    use crate::Play;
+    use crate::SimplePlay;
+    use crate::SimpleStep;
+    use crate::Step;
+    use crate::Story;
+    use crate::play;
+
+    /// Story used by the plays under test
+    #[derive(Debug, Default, Clone, Copy)]
+    struct TestStory;
+
+    impl Story for TestStory {}
+
+    /// Returns the descriptions of the steps of the `play`
+    fn steps(play: &dyn Play<Story = TestStory>) -> Vec<&'static str> {
+        play.steps().iter().map(|step| step.description()).collect()
+    }
+
+    /// Sub-play with the setup, a step and the teardown
+    fn sub_play() -> SimplePlay<TestStory> {
+        play! { TestStory: "Sub-play";
+            tag "sub";
+            before "Sub setup" => |_ctx| Ok(());
+            step "Sub step" => |_ctx| Ok(());
+            after "Sub teardown" => |_ctx| Ok(());
+        }
+    }
+
+    /// Every arm of the macro adds its part of the play in the written order
+    #[test]
+    fn macro_arms() {
+        let shared = SimpleStep::new("Shared step", |_ctx| Ok(()));
+
+        let play: SimplePlay<TestStory> = play! { TestStory: "Macro play";
+            tag "smoke";
+            tag "keyboard";
+            tag "smoke";
+            before "Setup" => |_ctx| Ok(());
+            step "First step" => |_ctx| Ok(());
+            timeout Duration::from_millis(500);
+            step async "Async step" => async |_ctx| Ok(());
+            budget Duration::from_millis(50);
+            reuse shared;
+            include sub_play();
+            after "Teardown" => |_ctx| Ok(());
+        };
+
+        assert_eq!(play.description(), "Macro play");
+        assert_eq!(play.tags(), vec!["smoke", "keyboard"]);
+        assert_eq!(
+            Play::before(&play).map(|step| step.description()),
+            Some("Setup")
+        );
+        assert_eq!(
+            Play::after(&play).map(|step| step.description()),
+            Some("Teardown")
+        );
+        assert_eq!(
+            steps(&play),
+            vec![
+                "First step",
+                "Async step",
+                "Shared step",
+                "Sub setup",
+                "Sub step",
+                "Sub teardown",
+            ]
+        );
+
+        let steps = play.steps();
+        assert_eq!(steps[0].timeout(), Some(Duration::from_millis(500)));
+        assert_eq!(steps[0].budget(), None);
+        assert_eq!(steps[1].timeout(), None);
+        assert_eq!(steps[1].budget(), Some(Duration::from_millis(50)));
+    }
+
+    /// Setup and teardown of the sub-play become its ordinary steps, its tags are not included
+    #[test]
+    fn included_sub_play() {
+        let play = play::<TestStory>("Play")
+            .next("Before the sub-play", |_ctx| Ok(()))
+            .include(sub_play())
+            .next("After the sub-play", |_ctx| Ok(()));
+
+        assert_eq!(
+            steps(&play),
+            vec![
+                "Before the sub-play",
+                "Sub setup",
+                "Sub step",
+                "Sub teardown",
+                "After the sub-play",
+            ]
+        );
+        assert!(Play::before(&play).is_none());
+        assert!(Play::after(&play).is_none());
+        assert!(play.tags().is_empty());
+    }
+
+    /// Timeout and budget set right after the included sub-play apply to its last step
+    #[test]
+    fn included_sub_play_keeps_step_settings() {
+        let sub_play = play::<TestStory>("Sub-play")
+            .next("Slow step", |_ctx| Ok(()))
+            .timeout(Duration::from_secs(1));
+
+        let play = play::<TestStory>("Play")
+            .include(sub_play)
+            .budget(Duration::from_millis(10));
+
+        let steps = play.steps();
+        assert_eq!(steps[0].timeout(), Some(Duration::from_secs(1)));
+        assert_eq!(steps[0].budget(), Some(Duration::from_millis(10)));
+    }
+}
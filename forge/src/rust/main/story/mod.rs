@@ -1,24 +1,58 @@
 //! Defines story interface and all of fancy stuff that goes with it
 //!
 
+mod context;
+mod failure;
+mod macros;
+#[cfg(feature = "proptest")]
+mod property;
+mod table;
 mod tests;
+mod timer;
+mod wait;
 
-use leptos::web_sys::HtmlElement;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
 
+pub(crate) use context::Scratch;
+pub use context::StepContext;
+pub use failure::StepFailure;
+pub use failure::StepResult;
 use leptos::IntoView;
+#[cfg(feature = "proptest")]
+pub use property::PropertyPlay;
+#[cfg(feature = "proptest")]
+pub use property::property;
+pub use table::table;
+pub use tests::SimplePlay;
+pub use tests::SimpleStep;
 pub use tests::play;
 pub use tests::test_id;
+pub use timer::Sleep;
+pub(crate) use timer::next_frame;
+pub(crate) use timer::now;
+pub use timer::sleep;
 use utils::prelude::ThreadSafe;
+pub use wait::POLL_INTERVAL;
+pub(crate) use wait::deadline;
+pub(crate) use wait::remaining;
+pub use wait::wait_for;
+pub(crate) use wait::with_timeout;
 
 use crate::RouteDef;
 
+/// Future returned by the [Step::run_async]
+pub type StepFuture<'a> = Pin<Box<dyn Future<Output = StepResult> + 'a>>;
+
 /// One step in the testing process
 ///
 /// Step is an ephemeral description of a single step in the testing process.
 /// It will be created and destroyed whenever it's needed, even in the middle of
 /// the testing process.
 ///
-/// Do not store any data in the structures which implement this trait
+/// Do not store any data in the structures which implement this trait. Values
+/// needed by the later steps of the play are stored in the [StepContext].
 pub trait Step {
     /// Story for which the step is defined
     type Story: Story;
@@ -26,16 +60,57 @@ pub trait Step {
     fn description(&self) -> &'static str;
     /// Play the step
     ///
-    /// If the step fails, it should return an error message using
-    /// Markdown so it can display it nicely in the UI
+    /// # Errors
+    ///
+    /// If the step fails, it should return a [StepFailure] which can be displayed in the UI.
+    /// Message and details of the failure are formatted in Markdown.
+    fn run(&self, ctx: &mut StepContext<Self::Story>) -> StepResult;
+
+    /// Play the step asynchronously
+    ///
+    /// The test runner always calls this method and waits for the returned
+    /// future to complete before it moves to the next step. The default
+    /// implementation just calls [Step::run].
+    ///
+    /// Override it when the step must wait for something before it can check
+    /// the result, like a timeout, a `Resource`, a `Suspense` boundary or an
+    /// animation.
     ///
     /// # Errors
     ///
-    /// If the step fails, it should return an error message which can be displayed in the UI
-    /// formatted in Markdown.
-    fn run(&self, canvas: &HtmlElement, story: &mut Self::Story) -> Result<(), &'static str>;
+    /// Same as for the [Step::run], if the step fails the future resolves to a
+    /// [StepFailure].
+    fn run_async<'a>(&'a self, ctx: &'a mut StepContext<Self::Story>) -> StepFuture<'a> {
+        Box::pin(std::future::ready(self.run(ctx)))
+    }
+
+    /// How long the step can be retried before it fails
+    ///
+    /// If it's set, the failed step is run again every [POLL_INTERVAL] until it
+    /// passes or the timeout expires. A step which doesn't complete before the
+    /// timeout fails with the "timed out" message. It's useful for DOM updates
+    /// which land after the reactive system flushes.
+    ///
+    /// If it's `None`, the step is run once and it fails if it doesn't complete
+    /// within the [DEFAULT_STEP_TIMEOUT].
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// How long the step may take before it's considered too slow
+    ///
+    /// If it's set, the step which has passed, but took longer than the budget,
+    /// fails. Use it to catch the regressions in the rendering performance of
+    /// heavy components. The duration includes the render triggered by the step
+    /// and the retries of the step with the [Step::timeout].
+    fn budget(&self) -> Option<Duration> {
+        None
+    }
 }
 
+/// How long the test runner waits for the step which doesn't define its own [Step::timeout]
+pub const DEFAULT_STEP_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// A play for a story
 ///
 /// Play is an ephemeral container for a list of steps. It will be freely created and destroyed as needed
@@ -47,6 +122,32 @@ pub trait Play {
     fn description(&self) -> &'static str;
     /// List of steps in the play   
     fn steps(&self) -> Vec<Box<dyn Step<Story = Self::Story>>>;
+
+    /// Tags of the play, like `smoke`, `keyboard` or `slow`
+    ///
+    /// Tags are used to select the plays which are shown in the test viewer and
+    /// run by the test runner.
+    fn tags(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Setup step run before the first step of the play
+    ///
+    /// Use it to seed the signals or install the mocks required by the play.
+    /// If it fails, none of the steps of the play is run.
+    fn before(&self) -> Option<Box<dyn Step<Story = Self::Story>>> {
+        None
+    }
+
+    /// Teardown step run after the last step of the play
+    ///
+    /// It's always run, even if the setup or any of the steps has failed or the
+    /// play was stopped, so you can use it to clean up the global state like
+    /// event listeners. The only exception is the play run step by step in the
+    /// test viewer and abandoned by resetting the story, stop it to run the teardown.
+    fn after(&self) -> Option<Box<dyn Step<Story = Self::Story>>> {
+        None
+    }
 }
 
 impl<T: Play + ?Sized> Play for Box<T> {
@@ -57,6 +158,15 @@ impl<T: Play + ?Sized> Play for Box<T> {
     fn steps(&self) -> Vec<Box<dyn Step<Story = Self::Story>>> {
         self.as_ref().steps()
     }
+    fn tags(&self) -> Vec<&'static str> {
+        self.as_ref().tags()
+    }
+    fn before(&self) -> Option<Box<dyn Step<Story = Self::Story>>> {
+        self.as_ref().before()
+    }
+    fn after(&self) -> Option<Box<dyn Step<Story = Self::Story>>> {
+        self.as_ref().after()
+    }
 }
 
 /// Short tutorial/cheat sheet about [Story] trait implementation
@@ -108,6 +218,11 @@ While creating a description you should try to explain
 
 "############;
 
+/// Returns `true` if the `description` is the cheat sheet returned by the default [Story::description]
+pub(crate) fn is_default_description(description: &str) -> bool {
+    description.trim() == STORY_DESC.trim()
+}
+
 /// Story to show in the application
 ///
 /// # Why `Story` must implement `Copy`?
@@ -130,6 +245,20 @@ pub trait Story: Default + Copy {
         Vec::new()
     }
 
+    /// Setup step run before every play of the story
+    ///
+    /// It's run before the [Play::before] step of the play.
+    fn before_each(&self) -> Option<Box<dyn Step<Story = Self>>> {
+        None
+    }
+
+    /// Teardown step run after every play of the story
+    ///
+    /// It's run after the [Play::after] step of the play, even if the play has failed.
+    fn after_each(&self) -> Option<Box<dyn Step<Story = Self>>> {
+        None
+    }
+
     /// Returns the list of subroutes for the story
     fn subroutes(&self) -> Vec<RouteDef> {
         vec![]
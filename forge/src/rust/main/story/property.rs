@@ -0,0 +1,484 @@
+//! Property based plays driven by the `proptest` strategies
+//!
+//! Property play samples the inputs from the [Strategy], applies every input to
+//! the story and runs the checks against the canvas. When a check fails, the input
+//! is shrunk to the smallest one which still fails, and it's shown in the failure
+//! together with the seed of the generator, which replays the same inputs.
+
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::time::Duration;
+
+use leptos::web_sys::js_sys::Math;
+use proptest::strategy::Strategy;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::Config;
+use proptest::test_runner::RngAlgorithm;
+use proptest::test_runner::TestRng;
+use proptest::test_runner::TestRunner;
+
+use super::Play;
+use super::Step;
+use super::StepContext;
+use super::StepFailure;
+use super::StepFuture;
+use super::StepResult;
+use super::Story;
+use super::sleep;
+
+/// Number of cases checked by default
+const DEFAULT_CASES: u32 = 32;
+/// Maximum number of the shrinking steps by default
+const DEFAULT_MAX_SHRINK_ITERS: u32 = 256;
+/// Description of the step which runs the cases
+const STEP_DESCRIPTION: &str = "Check the property for the generated inputs";
+
+/// Type of the function which applies the generated input to the story
+///
+/// Same as the steps, it's a closure, so it can capture the parameters of the
+/// function which builds the play.
+type ApplyFn<S, T> = Rc<dyn Fn(&mut S, &T)>;
+
+/// Type of the function which checks the story for the generated input
+type CheckFn<S, T> = Rc<dyn Fn(&mut StepContext<S>, &T) -> StepResult>;
+
+/// Named check of the property
+struct Check<S, T> {
+    /// Description of the check shown in the failure
+    description: &'static str,
+    /// Function which checks the story
+    check: CheckFn<S, T>,
+}
+
+impl<S, T> Clone for Check<S, T> {
+    fn clone(&self) -> Self {
+        Self {
+            description: self.description,
+            check: Rc::clone(&self.check),
+        }
+    }
+}
+
+/// Failure of the single case
+struct CaseFailure {
+    /// Description of the failed check
+    check: &'static str,
+    /// Failure returned by the check
+    failure: StepFailure,
+}
+
+/// Play which checks the property of the story for the inputs generated by the [Strategy]
+///
+/// Use the [property] function to create it.
+pub struct PropertyPlay<S: Story, St: Strategy> {
+    /// Description of the play
+    description: &'static str,
+    /// Strategy generating the inputs
+    strategy: Rc<St>,
+    /// Applies the input to the story
+    apply: Option<ApplyFn<S, St::Value>>,
+    /// Checks run for every input
+    checks: Vec<Check<S, St::Value>>,
+    /// Number of the generated cases
+    cases: u32,
+    /// Maximum number of the shrinking steps
+    max_shrink_iters: u32,
+    /// Seed of the generator, random for every run if it's `None`
+    seed: Option<[u8; 32]>,
+    /// Tags of the play
+    tags: Vec<&'static str>,
+}
+
+impl<S: Story, St: Strategy> PropertyPlay<S, St> {
+    /// Sets the function which applies the generated input to the story
+    ///
+    /// It's called before the checks of every case, usually it sets the signals
+    /// of the story. The story isn't reset between the cases, so it must set
+    /// every signal which the checks read, not only the ones changed by the input.
+    pub fn apply(mut self, apply: impl Fn(&mut S, &St::Value) + 'static) -> Self {
+        self.apply = Some(Rc::new(apply));
+        self
+    }
+
+    /// adds the check run for every generated input
+    pub fn check(
+        mut self,
+        description: &'static str,
+        check: impl Fn(&mut StepContext<S>, &St::Value) -> StepResult + 'static,
+    ) -> Self {
+        self.checks.push(Check {
+            description,
+            check: Rc::new(check),
+        });
+        self
+    }
+
+    /// sets the number of the generated cases, `32` by default
+    pub fn cases(mut self, cases: u32) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    /// sets the maximum number of the shrinking steps, `256` by default
+    pub fn max_shrink_iters(mut self, max_shrink_iters: u32) -> Self {
+        self.max_shrink_iters = max_shrink_iters;
+        self
+    }
+
+    /// sets the seed of the generator, so every run checks the same inputs
+    ///
+    /// The seed of the failed run is shown in the failure, use it to replay the
+    /// failing inputs while you fix the story.
+    pub fn seed(mut self, seed: [u8; 32]) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// adds the tag to the play, see [SimplePlay::tag][super::SimplePlay::tag]
+    pub fn tag(mut self, tag: &'static str) -> Self {
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+        self
+    }
+}
+
+impl<S, St> From<PropertyPlay<S, St>> for Box<dyn Play<Story = S>>
+where
+    S: Story + 'static,
+    St: Strategy + 'static,
+    St::Value: Debug,
+{
+    fn from(value: PropertyPlay<S, St>) -> Self {
+        Box::new(value)
+    }
+}
+
+impl<S, St> Play for PropertyPlay<S, St>
+where
+    S: Story + 'static,
+    St: Strategy + 'static,
+    St::Value: Debug,
+{
+    type Story = S;
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn steps(&self) -> Vec<Box<dyn Step<Story = Self::Story>>> {
+        vec![Box::new(PropertyStep {
+            strategy: Rc::clone(&self.strategy),
+            apply: self.apply.clone(),
+            checks: self.checks.clone(),
+            cases: self.cases,
+            max_shrink_iters: self.max_shrink_iters,
+            seed: self.seed,
+        })]
+    }
+
+    fn tags(&self) -> Vec<&'static str> {
+        self.tags.clone()
+    }
+}
+
+/// The only step of the [PropertyPlay], which runs all of the cases
+struct PropertyStep<S: Story, St: Strategy> {
+    /// Strategy generating the inputs
+    strategy: Rc<St>,
+    /// Applies the input to the story
+    apply: Option<ApplyFn<S, St::Value>>,
+    /// Checks run for every input
+    checks: Vec<Check<S, St::Value>>,
+    /// Number of the generated cases
+    cases: u32,
+    /// Maximum number of the shrinking steps
+    max_shrink_iters: u32,
+    /// Seed of the generator, random for every run if it's `None`
+    seed: Option<[u8; 32]>,
+}
+
+/// Shrinking of the failing input
+///
+/// Same as in `proptest`: the input is simplified while the case fails and
+/// complicated when it passes, until it can't be changed any more or the
+/// maximum number of the shrinking steps is reached.
+struct Shrinking<Tree: ValueTree, E> {
+    /// Tree of the failing input
+    tree: Tree,
+    /// Smallest failing input found so far and its failure
+    minimal: (Tree::Value, E),
+    /// Number of the shrinking steps made so far
+    shrinks: u32,
+    /// Maximum number of the shrinking steps
+    max_shrink_iters: u32,
+    /// `false` when the input can't be changed any more
+    shrinking: bool,
+}
+
+impl<Tree: ValueTree, E> Shrinking<Tree, E> {
+    /// Starts shrinking the current input of the `tree`, which has failed with the `failure`
+    fn new(mut tree: Tree, failure: E, max_shrink_iters: u32) -> Self {
+        let original = tree.current();
+        let shrinking = tree.simplify();
+
+        Self {
+            tree,
+            minimal: (original, failure),
+            shrinks: 0,
+            max_shrink_iters,
+            shrinking,
+        }
+    }
+
+    /// Returns the next input to check, `None` when the shrinking is done
+    fn candidate(&mut self) -> Option<Tree::Value> {
+        if !self.shrinking || self.shrinks >= self.max_shrink_iters {
+            return None;
+        }
+
+        self.shrinks += 1;
+        Some(self.tree.current())
+    }
+
+    /// Records the result of checking the `candidate`
+    fn record(&mut self, candidate: Tree::Value, result: Result<(), E>) {
+        self.shrinking = match result {
+            Err(failure) => {
+                self.minimal = (candidate, failure);
+                self.tree.simplify()
+            }
+            Ok(()) => self.tree.complicate(),
+        };
+    }
+
+    /// Returns the smallest failing input, its failure and the number of the shrinking steps
+    fn finish(self) -> (Tree::Value, E, u32) {
+        let (input, failure) = self.minimal;
+        (input, failure, self.shrinks)
+    }
+}
+
+impl<S: Story, St: Strategy> PropertyStep<S, St>
+where
+    St::Value: Debug,
+{
+    /// Applies the input to the story and runs all of the checks
+    ///
+    /// The browser gets a moment between applying the input and running the checks,
+    /// so the DOM can catch up with the signals.
+    async fn run_case(
+        &self,
+        ctx: &mut StepContext<S>,
+        input: &St::Value,
+    ) -> Result<(), CaseFailure> {
+        if let Some(apply) = &self.apply {
+            apply(ctx.story_mut(), input);
+        }
+
+        sleep(Duration::ZERO).await;
+
+        for check in &self.checks {
+            (check.check)(ctx, input).map_err(|failure| CaseFailure {
+                check: check.description,
+                failure,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs all of the cases and shrinks the first failing one
+    async fn run_cases(&self, ctx: &mut StepContext<S>) -> StepResult {
+        let config = Config {
+            cases: self.cases,
+            max_shrink_iters: self.max_shrink_iters,
+            ..Config::default()
+        };
+        let seed = self.seed.unwrap_or_else(random_seed);
+        let mut runner =
+            TestRunner::new_with_rng(config, TestRng::from_seed(RngAlgorithm::ChaCha, &seed));
+
+        for passed in 0..self.cases {
+            let tree = self.strategy.new_tree(&mut runner).map_err(|reason| {
+                StepFailure::new(format!(
+                    "Unable to generate the input: {}",
+                    reason.message()
+                ))
+            })?;
+
+            let Err(failure) = self.run_case(ctx, &tree.current()).await else {
+                continue;
+            };
+
+            let mut shrinking = Shrinking::new(tree, failure, self.max_shrink_iters);
+            while let Some(candidate) = shrinking.candidate() {
+                let result = self.run_case(ctx, &candidate).await;
+                shrinking.record(candidate, result);
+            }
+
+            let (input, CaseFailure { check, failure }, shrinks) = shrinking.finish();
+
+            return Err(
+                StepFailure::new(format!("Property failed for the input `{input:?}`"))
+                    .with_details(format!(
+                        "Failed check: **{check}**\n\n{}\n\n- Passed cases: {passed}\n\
+                     - Shrinking steps: {shrinks}\n\
+                     - Seed: `{seed:?}`, replay the same inputs with `.seed({seed:?})`",
+                        failure.to_markdown()
+                    )),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl<S, St> Step for PropertyStep<S, St>
+where
+    S: Story,
+    St: Strategy,
+    St::Value: Debug,
+{
+    type Story = S;
+
+    fn description(&self) -> &'static str {
+        STEP_DESCRIPTION
+    }
+
+    fn run(&self, _ctx: &mut StepContext<Self::Story>) -> StepResult {
+        Err(StepFailure::new(
+            "This step is asynchronous and it must be played using `Step::run_async`",
+        ))
+    }
+
+    fn run_async<'a>(&'a self, ctx: &'a mut StepContext<Self::Story>) -> StepFuture<'a> {
+        Box::pin(self.run_cases(ctx))
+    }
+}
+
+/// Returns the random seed of the generator
+///
+/// The operating system's random generator is not available in the browser, so
+/// the seed comes from the `Math.random()`.
+fn random_seed() -> [u8; 32] {
+    let mut seed = [0u8; 32];
+
+    for chunk in seed.chunks_mut(4) {
+        let value = (Math::random() * f64::from(u32::MAX)) as u32;
+        chunk.copy_from_slice(&value.to_le_bytes());
+    }
+
+    seed
+}
+
+/// Create a property based play for the story
+///
+/// For every input generated by the `strategy`, the input is applied to the
+/// story and all of the checks are run. If any check fails, the input is shrunk
+/// and the smallest failing input is shown in the test viewer together with the
+/// seed, which can be passed to [PropertyPlay::seed] to replay the same inputs.
+///
+/// The story is not reset between the cases, every case starts with the story
+/// left by the previous one. The function passed to [PropertyPlay::apply] must
+/// set every signal which the checks read.
+///
+/// ```rust,ignore
+/// use leptos_forge::property;
+/// use leptos_forge::testing::*;
+///
+/// property::<CounterStory, _>("Message is shown above the threshold", 0..20_000u32)
+///     .apply(|story, value| story.value.set(*value))
+///     .check("Check the message", |ctx, value| {
+///         let message = ctx.get_by_test_id("message")?;
+///
+///         if *value > ctx.story().threshold.get_untracked() {
+///             assert_text(&message, &ctx.story().message.get_untracked())
+///         } else {
+///             assert_text(&message, "")
+///         }
+///     })
+///     .cases(64)
+///     .into()
+/// ```
+pub fn property<S: Story, St: Strategy>(
+    description: &'static str,
+    strategy: St,
+) -> PropertyPlay<S, St> {
+    PropertyPlay {
+        description,
+        strategy: Rc::new(strategy),
+        apply: None,
+        checks: Vec::new(),
+        cases: DEFAULT_CASES,
+        max_shrink_iters: DEFAULT_MAX_SHRINK_ITERS,
+        seed: None,
+        tags: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+/// Tests of the shrinking of the failing inputs
+mod tests {
+    use proptest::strategy::Strategy;
+    use proptest::strategy::ValueTree;
+    use proptest::test_runner::Config;
+    use proptest::test_runner::RngAlgorithm;
+    use proptest::test_runner::TestRng;
+    use proptest::test_runner::TestRunner;
+
+    use super::Shrinking;
+
+    /// Property which fails for the numbers from `100` up
+    fn check(value: u32) -> Result<(), u32> {
+        if value >= 100 { Err(value) } else { Ok(()) }
+    }
+
+    /// Returns the tree of the number which fails the [check]
+    fn failing_tree() -> impl ValueTree<Value = u32> {
+        let rng = TestRng::from_seed(RngAlgorithm::ChaCha, &[7; 32]);
+        let mut runner = TestRunner::new_with_rng(Config::default(), rng);
+
+        loop {
+            let tree = (0..10_000u32).new_tree(&mut runner).unwrap();
+            if check(tree.current()).is_err() {
+                return tree;
+            }
+        }
+    }
+
+    /// Runs the shrinking to the end, checking every candidate
+    fn shrink(max_shrink_iters: u32) -> (u32, u32, u32) {
+        let tree = failing_tree();
+        let original = tree.current();
+        let mut shrinking = Shrinking::new(tree, original, max_shrink_iters);
+
+        while let Some(candidate) = shrinking.candidate() {
+            shrinking.record(candidate, check(candidate));
+        }
+
+        shrinking.finish()
+    }
+
+    /// Failing input is shrunk to the smallest one which still fails
+    #[test]
+    fn shrinks_to_minimal_input() {
+        let (input, failure, shrinks) = shrink(1024);
+
+        assert_eq!(input, 100);
+        assert_eq!(failure, 100);
+        assert!(shrinks > 0);
+    }
+
+    /// Shrinking stops after the maximum number of the shrinking steps
+    #[test]
+    fn shrinking_is_capped() {
+        let original = failing_tree().current();
+
+        assert_eq!(shrink(0), (original, original, 0));
+
+        let (input, _failure, shrinks) = shrink(3);
+        assert_eq!(shrinks, 3);
+        assert!((100..=original).contains(&input));
+    }
+}
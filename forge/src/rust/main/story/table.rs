@@ -0,0 +1,103 @@
+//! Table driven plays
+//!
+//! The same steps are run for every row of the table. Every row becomes its own
+//! play, so it shows up as a separate entry in the test viewer with its own result.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+use super::Play;
+use super::SimplePlay;
+use super::Story;
+use super::play;
+
+thread_local! {
+    /// Descriptions of the rows, which must live as long as the application
+    static DESCRIPTIONS: RefCell<HashSet<&'static str>> = RefCell::new(HashSet::new());
+}
+
+/// Returns the static copy of the text
+///
+/// Plays are recreated every time they are needed, so the same descriptions are
+/// requested over and over again. Every distinct text is leaked only once.
+fn intern(text: String) -> &'static str {
+    DESCRIPTIONS.with(|descriptions| {
+        let mut descriptions = descriptions.borrow_mut();
+
+        if let Some(description) = descriptions.get(text.as_str()) {
+            return *description;
+        }
+
+        let description: &'static str = Box::leak(text.into_boxed_str());
+        descriptions.insert(description);
+        description
+    })
+}
+
+/// Returns the description of the play for the row, like `Email is rejected: "a@"`
+fn row_description<T: Debug>(description: &str, row: &T) -> String {
+    format!("{description}: {row:?}")
+}
+
+/// Create a play for every row of the table
+///
+/// The `steps` function receives the play with the description of the row and
+/// the value of the row, and adds the steps to the play. The description of the
+/// row is the `description` followed by the value of the row.
+///
+/// ```rust,ignore
+/// fn plays(&self) -> Vec<Box<dyn Play<Story = Self>>> {
+///     table::<Self, _>("Invalid email is rejected", ["", "a@", "@b.c"], |play, email| {
+///         play.next("Type the email", move |ctx| {
+///             type_text(&ctx.get_by_label_text("Email")?, email)
+///         })
+///         .next("Check the error", |ctx| {
+///             assert_visible(&ctx.get_by_test_id("email-error")?)
+///         })
+///     })
+/// }
+/// ```
+pub fn table<S, T>(
+    description: &'static str,
+    rows: impl IntoIterator<Item = T>,
+    steps: impl Fn(SimplePlay<S>, T) -> SimplePlay<S>,
+) -> Vec<Box<dyn Play<Story = S>>>
+where
+    S: Story + 'static,
+    T: Debug,
+{
+    rows.into_iter()
+        .map(|row| {
+            let description = intern(row_description(description, &row));
+            steps(play::<S>(description), row).into()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+/// Tests of the table driven plays
+mod tests {
+    use super::intern;
+    use super::row_description;
+
+    /// Row description contains the value of the row
+    #[test]
+    fn description_of_row() {
+        assert_eq!(
+            row_description("Email is rejected", &"a@"),
+            r#"Email is rejected: "a@""#
+        );
+        assert_eq!(row_description("Threshold", &(1, 2)), "Threshold: (1, 2)");
+    }
+
+    /// The same text is leaked only once
+    #[test]
+    fn interned_descriptions() {
+        let first = intern("Email is rejected".to_string());
+        let second = intern("Email is rejected".to_string());
+
+        assert_eq!(first, second);
+        assert!(std::ptr::eq(first, second));
+    }
+}
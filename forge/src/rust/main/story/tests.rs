@@ -1,17 +1,57 @@
 //! Provides a builder for creating tests in the [stories][Story::]
 //!
 
+use std::pin::pin;
+use std::rc::Rc;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+use std::time::Duration;
+
 use super::Play;
 use super::Step;
+use super::StepContext;
+use super::StepFailure;
+use super::StepFuture;
+use super::StepResult;
 use super::Story;
 
 use leptos::attr::any_attribute::AnyAttribute;
 use leptos::prelude::IntoAnyAttribute;
 use leptos::view;
-use leptos::web_sys::HtmlElement;
 
 /// Type of the function which is used for steps in the [Play]
-type StepFn<S> = fn(canvas: &HtmlElement, &mut S) -> Result<(), &'static str>;
+///
+/// Steps are closures, so they can capture the parameters of the function which
+/// builds the play, like the number of clicks in a reusable sub-play.
+type StepFn<S> = Rc<dyn Fn(&mut StepContext<S>) -> StepResult>;
+
+/// Type of the function which is used for asynchronous steps in the [Play]
+///
+/// The returned future borrows the [StepContext] of the play, so the changes of
+/// the story and the stored values are seen by the next steps.
+type AsyncStepFn<S> = Rc<dyn for<'a> Fn(&'a mut StepContext<S>) -> StepFuture<'a>>;
+
+/// Fixes the signature of the closure wrapping the asynchronous step
+///
+/// Closure passed straight to the [Rc::new] doesn't get the signature of the
+/// [AsyncStepFn], so the lifetime of the future isn't tied to the context.
+fn async_step_fn<S, F>(step: F) -> F
+where
+    S: Story,
+    F: for<'a> Fn(&'a mut StepContext<S>) -> StepFuture<'a>,
+{
+    step
+}
+
+/// Function which is run when the [SimpleStep] is played
+#[derive(Clone)]
+enum StepBody<S: Story> {
+    /// Synchronous step
+    Sync(StepFn<S>),
+    /// Asynchronous step
+    Async(AsyncStepFn<S>),
+}
 
 /// Simple implementation of the step interface should be enough for most of the use cases
 #[derive(Clone)]
@@ -24,13 +64,61 @@ pub struct SimpleStep<S: Story> {
     /// It will be shown in the test runner UI.
     description: &'static str,
     /// Function to run when the step is played
-    step: StepFn<S>,
+    step: StepBody<S>,
+    /// How long the step can be retried before it fails
+    timeout: Option<Duration>,
+    /// How long the step may take before it's considered too slow
+    budget: Option<Duration>,
 }
 
 impl<S: Story> SimpleStep<S> {
     /// Create new instance of the SimpleStep
-    pub fn new(description: &'static str, step: StepFn<S>) -> Self {
-        Self { description, step }
+    ///
+    /// Shared steps can be created once and added to many plays with the
+    /// [SimplePlay::step].
+    ///
+    /// ```rust,ignore
+    /// fn click_plus(times: usize) -> SimpleStep<CounterStory> {
+    ///     SimpleStep::new("Click the + button", move |ctx| {
+    ///         for _ in 0..times {
+    ///             click(&ctx.get_by_text("+")?)?;
+    ///         }
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    pub fn new(
+        description: &'static str,
+        step: impl Fn(&mut StepContext<S>) -> StepResult + 'static,
+    ) -> Self {
+        Self {
+            description,
+            step: StepBody::Sync(Rc::new(step)),
+            timeout: None,
+            budget: None,
+        }
+    }
+
+    /// Create new instance of the asynchronous SimpleStep
+    ///
+    /// The step is an async closure borrowing the [StepContext] of the play.
+    pub fn new_async<F>(description: &'static str, step: F) -> Self
+    where
+        S: 'static,
+        F: AsyncFn(&mut StepContext<S>) -> StepResult + 'static,
+    {
+        let step = Rc::new(step);
+        let step: AsyncStepFn<S> = Rc::new(async_step_fn(move |ctx| {
+            let step = Rc::clone(&step);
+            Box::pin(async move { (*step)(ctx).await })
+        }));
+
+        Self {
+            description,
+            step: StepBody::Async(step),
+            timeout: None,
+            budget: None,
+        }
     }
 }
 
@@ -41,8 +129,38 @@ impl<S: Story> Step for SimpleStep<S> {
         self.description
     }
 
-    fn run(&self, canvas: &HtmlElement, story: &mut Self::Story) -> Result<(), &'static str> {
-        (self.step)(canvas, story)
+    /// Plays the step
+    ///
+    /// The asynchronous step is polled once, so it passes or fails only if it
+    /// doesn't have to wait for anything. Use [Step::run_async] to wait for it.
+    fn run(&self, ctx: &mut StepContext<Self::Story>) -> StepResult {
+        match &self.step {
+            StepBody::Sync(step) => step(ctx),
+            StepBody::Async(step) => {
+                let future = pin!(step(ctx));
+                match future.poll(&mut Context::from_waker(Waker::noop())) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => Err(StepFailure::new(
+                        "This step is still waiting, it must be played using `Step::run_async`",
+                    )),
+                }
+            }
+        }
+    }
+
+    fn run_async<'a>(&'a self, ctx: &'a mut StepContext<Self::Story>) -> StepFuture<'a> {
+        match &self.step {
+            StepBody::Sync(step) => Box::pin(std::future::ready(step(ctx))),
+            StepBody::Async(step) => step(ctx),
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn budget(&self) -> Option<Duration> {
+        self.budget
     }
 }
 
@@ -55,6 +173,7 @@ impl<S: Story + 'static> From<SimpleStep<S>> for Box<dyn Step<Story = S>> {
 /// The simple implementation of the play interface should be enough for most of the use cases
 ///
 /// It doubles as the factory for creating a tests
+#[derive(Clone)]
 pub struct SimplePlay<S: Story> {
     /// Description of the play
     ///
@@ -66,14 +185,190 @@ pub struct SimplePlay<S: Story> {
 
     /// list of steps to run a test
     steps: Vec<SimpleStep<S>>,
+
+    /// setup step run before the first step
+    before: Option<SimpleStep<S>>,
+
+    /// teardown step run after the last step, even if the play has failed
+    after: Option<SimpleStep<S>>,
+
+    /// tags of the play
+    tags: Vec<&'static str>,
 }
 
 impl<S: Story> SimplePlay<S> {
+    /// sets the setup step of the play
+    ///
+    /// If the setup step fails, none of the steps of the play is run.
+    pub fn before(
+        mut self,
+        name: &'static str,
+        step: impl Fn(&mut StepContext<S>) -> StepResult + 'static,
+    ) -> Self {
+        self.before = Some(SimpleStep::new(name, step));
+        self
+    }
+
+    /// sets the teardown step of the play
+    ///
+    /// The teardown step is run even if the setup or any of the steps has failed.
+    pub fn after(
+        mut self,
+        name: &'static str,
+        step: impl Fn(&mut StepContext<S>) -> StepResult + 'static,
+    ) -> Self {
+        self.after = Some(SimpleStep::new(name, step));
+        self
+    }
+
     /// adds next step to the play
-    pub fn next(mut self, name: &'static str, step: StepFn<S>) -> Self {
+    ///
+    /// The step is a closure, so it can capture the values it needs
+    ///
+    /// ```rust,ignore
+    /// let expected = format!("Value: {}", value);
+    ///
+    /// play::<CounterStory>("Counter shows the value")
+    ///     .next("Check the value", move |ctx| {
+    ///         assert_text(&ctx.get_by_test_id("value")?, &expected)
+    ///     })
+    /// ```
+    pub fn next(
+        mut self,
+        name: &'static str,
+        step: impl Fn(&mut StepContext<S>) -> StepResult + 'static,
+    ) -> Self {
         self.steps.push(SimpleStep::new(name, step));
         self
     }
+
+    /// adds the shared step to the play
+    ///
+    /// ```rust,ignore
+    /// play::<CounterStory>("Counter is increased")
+    ///     .step(click_plus(3))
+    ///     .next("Check the value", |ctx| { ... })
+    /// ```
+    pub fn step(mut self, step: SimpleStep<S>) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// adds all of the steps of the sub-play to the play
+    ///
+    /// Sub-plays are the reusable sequences of steps, usually returned by the
+    /// functions taking the parameters of the sequence. Setup and teardown steps
+    /// of the sub-play become ordinary steps of the play, at the same positions.
+    /// Tags of the sub-play are not added to the play.
+    ///
+    /// ```rust,ignore
+    /// fn fill_the_form(name: &'static str) -> SimplePlay<FormStory> {
+    ///     play::<FormStory>("Fill the form")
+    ///         .next("Type the name", move |ctx| {
+    ///             type_text(&ctx.get_by_label_text("Name")?, name)
+    ///         })
+    ///         .next("Submit", |ctx| click(&ctx.get_by_role("button")?))
+    /// }
+    ///
+    /// play::<FormStory>("Greeting is shown")
+    ///     .include(fill_the_form("Joe"))
+    ///     .next("Check the greeting", |ctx| { ... })
+    /// ```
+    pub fn include(mut self, play: SimplePlay<S>) -> Self {
+        self.steps.extend(play.before);
+        self.steps.extend(play.steps);
+        self.steps.extend(play.after);
+        self
+    }
+
+    /// adds next asynchronous step to the play
+    ///
+    /// The test runner waits for the returned future before it moves to the
+    /// next step, so the step can wait for a timeout, a `Resource`, a `Suspense`
+    /// boundary or an animation before it checks the results.
+    ///
+    /// ```rust,ignore
+    /// play::<MyStory>("Data is loaded")
+    ///     .next_async("Wait for the data", async |ctx| {
+    ///         ctx.sleep(Duration::from_millis(100)).await;
+    ///
+    ///         if ctx.canvas().inner_text().is_empty() {
+    ///             return Err("Data wasn't loaded".into());
+    ///         }
+    ///
+    ///         Ok(())
+    ///     })
+    /// ```
+    ///
+    /// The step borrows the [StepContext], so it can change the story with the
+    /// [StepContext::story_mut] and store the values for the next steps.
+    pub fn next_async<F>(mut self, name: &'static str, step: F) -> Self
+    where
+        S: 'static,
+        F: AsyncFn(&mut StepContext<S>) -> StepResult + 'static,
+    {
+        self.steps.push(SimpleStep::new_async(name, step));
+        self
+    }
+
+    /// sets the timeout of the last added step
+    ///
+    /// The step is retried until it passes or the timeout expires. Use it for the
+    /// DOM updates which land after the reactive system flushes.
+    ///
+    /// ```rust,ignore
+    /// play::<MyStory>("Counter is updated")
+    ///     .next("Click the button", |ctx| { ... })
+    ///     .next("Check the counter", |ctx| { ... })
+    ///     .timeout(Duration::from_millis(500))
+    /// ```
+    ///
+    /// If the play has no steps yet, it does nothing.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        if let Some(step) = self.steps.last_mut() {
+            step.timeout = Some(timeout);
+        }
+        self
+    }
+
+    /// sets the performance budget of the last added step
+    ///
+    /// The step fails if it takes longer than the `budget`, even if all of its
+    /// checks have passed. Use it to catch slow re-renders of heavy components.
+    ///
+    /// ```rust,ignore
+    /// play::<TableStory>("Sorting is fast")
+    ///     .next("Sort by the name", |ctx| {
+    ///         click(&ctx.get_by_text("Name")?)
+    ///     })
+    ///     .budget(Duration::from_millis(50))
+    /// ```
+    ///
+    /// If the play has no steps yet, it does nothing.
+    pub fn budget(mut self, budget: Duration) -> Self {
+        if let Some(step) = self.steps.last_mut() {
+            step.budget = Some(budget);
+        }
+        self
+    }
+
+    /// adds the tag to the play
+    ///
+    /// Tags, like `smoke`, `keyboard` or `slow`, select the plays shown in the
+    /// test viewer and run by the test runner.
+    ///
+    /// ```rust,ignore
+    /// play::<FormStory>("Form can be submitted with the keyboard")
+    ///     .tag("keyboard")
+    ///     .tag("smoke")
+    ///     .next("Press Enter", |ctx| press_key(&ctx.get_by_label_text("Name")?, "Enter"))
+    /// ```
+    pub fn tag(mut self, tag: &'static str) -> Self {
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+        self
+    }
 }
 
 impl<S: Story + 'static> From<SimplePlay<S>> for Box<dyn Play<Story = S>> {
@@ -95,6 +390,18 @@ impl<S: Story + 'static> Play for SimplePlay<S> {
             .map(|step| step.clone().into())
             .collect::<Vec<_>>()
     }
+
+    fn tags(&self) -> Vec<&'static str> {
+        self.tags.clone()
+    }
+
+    fn before(&self) -> Option<Box<dyn Step<Story = Self::Story>>> {
+        self.before.clone().map(Into::into)
+    }
+
+    fn after(&self) -> Option<Box<dyn Step<Story = Self::Story>>> {
+        self.after.clone().map(Into::into)
+    }
 }
 
 /// Create a play for the story
@@ -102,6 +409,9 @@ pub fn play<S: Story>(name: &'static str) -> SimplePlay<S> {
     SimplePlay {
         description: name,
         steps: Vec::new(),
+        before: None,
+        after: None,
+        tags: Vec::new(),
     }
 }
 
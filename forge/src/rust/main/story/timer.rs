@@ -0,0 +1,141 @@
+//! Timer future which can be awaited inside of the asynchronous steps
+//!
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+use std::time::Duration;
+
+use leptos::prelude::request_animation_frame;
+use leptos::prelude::set_timeout;
+
+/// State shared between the [Sleep] future and the browser timeout callback
+#[derive(Debug, Default)]
+struct SleepState {
+    /// Set to `true` when the timeout has fired
+    done: bool,
+    /// Waker of the task awaiting the [Sleep] future
+    waker: Option<Waker>,
+}
+
+/// Returns the browser callback which completes the future sharing the `state`
+fn wake(state: &Rc<RefCell<SleepState>>) -> impl FnOnce() + 'static {
+    let state = Rc::clone(state);
+
+    move || {
+        let waker = {
+            let mut state = state.borrow_mut();
+            state.done = true;
+            state.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Polls the future sharing the `state` with the browser callback
+fn poll_state(state: &RefCell<SleepState>, cx: &mut Context<'_>) -> Poll<()> {
+    let mut state = state.borrow_mut();
+    if state.done {
+        Poll::Ready(())
+    } else {
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Future returned by the [sleep] function
+///
+/// The browser timeout is scheduled when the future is polled for the first time.
+#[derive(Debug)]
+pub struct Sleep {
+    /// How long we should wait
+    duration: Duration,
+    /// State shared with the timeout callback
+    ///
+    /// It's `None` until the future is polled for the first time
+    state: Option<Rc<RefCell<SleepState>>>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let duration = self.duration;
+        let state = self.state.get_or_insert_with(|| {
+            let state = Rc::new(RefCell::new(SleepState::default()));
+            set_timeout(wake(&state), duration);
+            state
+        });
+
+        poll_state(state, cx)
+    }
+}
+
+/// Future returned by the [next_frame] function
+///
+/// The animation frame is requested when the future is polled for the first time.
+#[derive(Debug, Default)]
+pub(crate) struct NextFrame {
+    /// State shared with the animation frame callback
+    ///
+    /// It's `None` until the future is polled for the first time
+    state: Option<Rc<RefCell<SleepState>>>,
+}
+
+impl Future for NextFrame {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let state = self.state.get_or_insert_with(|| {
+            let state = Rc::new(RefCell::new(SleepState::default()));
+            request_animation_frame(wake(&state));
+            state
+        });
+
+        poll_state(state, cx)
+    }
+}
+
+/// Waits for the given `duration` without blocking the browser
+///
+/// Use it inside of the asynchronous steps when you need to give the browser
+/// a moment, for example to finish an animation.
+///
+/// ```rust,ignore
+/// play::<MyStory>("Dialog opens")
+///     .next_async("Wait for the animation to finish", async |_ctx| {
+///         sleep(Duration::from_millis(300)).await;
+///         Ok(())
+///     })
+/// ```
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        duration,
+        state: None,
+    }
+}
+
+/// Returns the current time in milliseconds measured by the browser's high
+/// resolution clock
+///
+/// If the clock is not available it returns `0.0`
+pub(crate) fn now() -> f64 {
+    leptos::prelude::window()
+        .performance()
+        .map(|performance| performance.now())
+        .unwrap_or_default()
+}
+
+/// Waits until the browser is about to paint the next frame
+///
+/// Everything which was mounted before is laid out and rendered in that frame.
+pub(crate) fn next_frame() -> NextFrame {
+    NextFrame::default()
+}
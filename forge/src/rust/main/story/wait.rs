@@ -0,0 +1,101 @@
+//! Helpers for waiting on the DOM updates inside of the steps
+//!
+
+use std::future::Future;
+use std::future::poll_fn;
+use std::pin::pin;
+use std::task::Poll;
+use std::time::Duration;
+
+use super::StepFailure;
+use super::StepResult;
+use super::now;
+use super::sleep;
+
+/// How long we wait between two checks of the condition or two attempts to run a step
+pub const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Runs the `future` until it completes or the `timeout` expires
+///
+/// Returns `None` if the `timeout` has expired before the `future` completed.
+pub(crate) async fn with_timeout<F: Future>(future: F, timeout: Duration) -> Option<F::Output> {
+    let mut future = pin!(future);
+    let mut timer = pin!(sleep(timeout));
+
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+
+        if timer.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(None);
+        }
+
+        Poll::Pending
+    })
+    .await
+}
+
+/// Returns the point in time, measured with [now], after the `timeout` from now
+pub(crate) fn deadline(timeout: Duration) -> f64 {
+    now() + timeout.as_secs_f64() * 1000.0
+}
+
+/// Time left until the `deadline` measured with [now]
+///
+/// Returns `None` if the `deadline` has already passed.
+pub(crate) fn remaining(deadline: f64) -> Option<Duration> {
+    let remaining = deadline - now();
+
+    if remaining > 0.0 {
+        Some(Duration::from_secs_f64(remaining / 1000.0))
+    } else {
+        None
+    }
+}
+
+/// Checks the `condition` until it passes or the `timeout` expires
+///
+/// Condition is checked every [POLL_INTERVAL]. Use it inside of the asynchronous
+/// steps when the DOM is updated after the reactive system flushes, for example
+/// after a `Resource` has loaded.
+///
+/// ```rust,ignore
+/// play::<MyStory>("Data is loaded")
+///     .next_async("Wait for the data", async |ctx| {
+///         wait_for(
+///             || {
+///                 if ctx.canvas().inner_text().is_empty() {
+///                     Err("Data wasn't loaded".into())
+///                 } else {
+///                     Ok(())
+///                 }
+///             },
+///             Duration::from_secs(1),
+///         )
+///         .await
+///     })
+/// ```
+///
+/// # Errors
+///
+/// If the `condition` doesn't pass before the `timeout` expires, it returns the
+/// last failure of the condition with the message saying how long we have waited.
+pub async fn wait_for<F>(mut condition: F, timeout: Duration) -> StepResult
+where
+    F: FnMut() -> StepResult,
+{
+    let deadline = deadline(timeout);
+
+    loop {
+        let last = match condition() {
+            Ok(()) => return Ok(()),
+            Err(failure) => failure,
+        };
+
+        match remaining(deadline) {
+            Some(remaining) => sleep(remaining.min(POLL_INTERVAL)).await,
+            None => return Err(StepFailure::timed_out(timeout, Some(last))),
+        }
+    }
+}
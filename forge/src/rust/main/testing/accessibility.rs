@@ -0,0 +1,598 @@
+//! Accessibility audit of the canvas
+//!
+//! The audit is a small set of rules written in Rust. It doesn't replace the full
+//! accessibility testing, but it catches the most common mistakes while the
+//! component is being developed.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use leptos::prelude::window;
+use leptos::web_sys::Element;
+use leptos::web_sys::HtmlElement;
+
+use super::assertions::is_visible;
+use super::describe;
+use super::queries::descendants;
+use super::queries::own_text;
+use super::queries::role;
+use super::queries::text;
+use crate::StepFailure;
+use crate::StepResult;
+
+/// Roles defined by the WAI-ARIA 1.2 which can be used in the `role` attribute
+const ARIA_ROLES: [&str; 82] = [
+    "alert",
+    "alertdialog",
+    "application",
+    "article",
+    "banner",
+    "blockquote",
+    "button",
+    "caption",
+    "cell",
+    "checkbox",
+    "code",
+    "columnheader",
+    "combobox",
+    "complementary",
+    "contentinfo",
+    "definition",
+    "deletion",
+    "dialog",
+    "directory",
+    "document",
+    "emphasis",
+    "feed",
+    "figure",
+    "form",
+    "generic",
+    "grid",
+    "gridcell",
+    "group",
+    "heading",
+    "img",
+    "insertion",
+    "link",
+    "list",
+    "listbox",
+    "listitem",
+    "log",
+    "main",
+    "marquee",
+    "math",
+    "menu",
+    "menubar",
+    "menuitem",
+    "menuitemcheckbox",
+    "menuitemradio",
+    "meter",
+    "navigation",
+    "none",
+    "note",
+    "option",
+    "paragraph",
+    "presentation",
+    "progressbar",
+    "radio",
+    "radiogroup",
+    "region",
+    "row",
+    "rowgroup",
+    "rowheader",
+    "scrollbar",
+    "search",
+    "searchbox",
+    "separator",
+    "slider",
+    "spinbutton",
+    "status",
+    "strong",
+    "subscript",
+    "superscript",
+    "switch",
+    "tab",
+    "table",
+    "tablist",
+    "tabpanel",
+    "term",
+    "textbox",
+    "time",
+    "timer",
+    "toolbar",
+    "tooltip",
+    "tree",
+    "treegrid",
+    "treeitem",
+];
+
+/// Minimal contrast ratio of the normal text required by the WCAG AA
+pub(crate) const NORMAL_TEXT_CONTRAST: f64 = 4.5;
+/// Minimal contrast ratio of the large text required by the WCAG AA
+pub(crate) const LARGE_TEXT_CONTRAST: f64 = 3.0;
+
+/// Rule checked by the accessibility audit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// Images must have the `alt` attribute
+    ImageAlt,
+    /// Form controls must have a label
+    FormLabel,
+    /// Ids must be unique
+    DuplicateId,
+    /// Roles must be valid ARIA roles
+    AriaRole,
+    /// Buttons must have an accessible name
+    ButtonName,
+    /// Text must have a sufficient contrast with its background
+    ColorContrast,
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Rule::*;
+        match self {
+            ImageAlt => f.write_str("image-alt"),
+            FormLabel => f.write_str("form-label"),
+            DuplicateId => f.write_str("duplicate-id"),
+            AriaRole => f.write_str("aria-role"),
+            ButtonName => f.write_str("button-name"),
+            ColorContrast => f.write_str("color-contrast"),
+        }
+    }
+}
+
+/// Problem found by the accessibility audit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Rule which was violated
+    pub rule: Rule,
+    /// Element which violates the rule
+    pub element: HtmlElement,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "**{}** `{}`: {}",
+            self.rule,
+            describe(&self.element),
+            self.message
+        )
+    }
+}
+
+/// Color in the sRGB color space with the alpha channel
+///
+/// Channels are in the `0..=255` range and alpha in the `0..=1` range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Rgba {
+    /// Red channel
+    pub(crate) r: f64,
+    /// Green channel
+    pub(crate) g: f64,
+    /// Blue channel
+    pub(crate) b: f64,
+    /// Alpha channel
+    pub(crate) a: f64,
+}
+
+/// White color, which is the default background of the page
+const WHITE: Rgba = Rgba {
+    r: 255.0,
+    g: 255.0,
+    b: 255.0,
+    a: 1.0,
+};
+
+impl Rgba {
+    /// Parses the computed value of the color, like `rgb(0, 0, 0)` or `rgba(0 0 0 / 0.5)`
+    ///
+    /// Returns `None` if the color is in a different format.
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+
+        if value == "transparent" {
+            return Some(Rgba { a: 0.0, ..WHITE });
+        }
+
+        let inner = value
+            .strip_prefix("rgba(")
+            .or_else(|| value.strip_prefix("rgb("))?
+            .strip_suffix(')')?;
+
+        let channels = inner
+            .split([',', '/', ' '])
+            .filter(|channel| !channel.is_empty())
+            .map(|channel| channel.parse::<f64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        let (r, g, b, a) = match channels.as_slice() {
+            [r, g, b] => (*r, *g, *b, 1.0),
+            [r, g, b, a] => (*r, *g, *b, *a),
+            _ => return None,
+        };
+
+        Some(Rgba { r, g, b, a })
+    }
+
+    /// Returns the color painted over the opaque `background`
+    pub(crate) fn over(&self, background: Rgba) -> Rgba {
+        let blend = |top: f64, bottom: f64| top * self.a + bottom * (1.0 - self.a);
+
+        Rgba {
+            r: blend(self.r, background.r),
+            g: blend(self.g, background.g),
+            b: blend(self.b, background.b),
+            a: 1.0,
+        }
+    }
+
+    /// Relative luminance of the color as defined by the WCAG
+    pub(crate) fn luminance(&self) -> f64 {
+        let channel = |value: f64| {
+            let value = value / 255.0;
+            if value <= 0.039_28 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        0.2126 * channel(self.r) + 0.7152 * channel(self.g) + 0.0722 * channel(self.b)
+    }
+}
+
+/// Contrast ratio of two opaque colors as defined by the WCAG, from `1` to `21`
+pub(crate) fn contrast_ratio(first: Rgba, second: Rgba) -> f64 {
+    let first = first.luminance();
+    let second = second.luminance();
+    let (lighter, darker) = if first > second {
+        (first, second)
+    } else {
+        (second, first)
+    };
+
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// Returns the contrast ratio required for the text of the given size in pixels and weight
+///
+/// Large text is at least `24px`, or at least `18.66px` if it's bold.
+pub(crate) fn required_contrast(font_size: f64, font_weight: u32) -> f64 {
+    if font_size >= 24.0 || (font_size >= 18.66 && font_weight >= 700) {
+        LARGE_TEXT_CONTRAST
+    } else {
+        NORMAL_TEXT_CONTRAST
+    }
+}
+
+/// Returns `true` if the `role` is a valid ARIA role
+pub(crate) fn is_valid_role(role: &str) -> bool {
+    ARIA_ROLES.contains(&role)
+}
+
+/// Returns the value of the computed style property of the element
+fn computed(element: &Element, property: &str) -> Option<String> {
+    window()
+        .get_computed_style(element)
+        .ok()
+        .flatten()
+        .and_then(|style| style.get_property_value(property).ok())
+}
+
+/// Returns the element with the given id inside of the canvas
+fn by_id(elements: &[HtmlElement], id: &str) -> Option<HtmlElement> {
+    elements.iter().find(|element| element.id() == id).cloned()
+}
+
+/// Returns the accessible name of the element
+///
+/// It's a simplified version of the accessible name computation, which takes into
+/// account `aria-labelledby`, `aria-label`, `<label>` elements, the `alt` of the
+/// images, the text content and the `title`.
+fn accessible_name(elements: &[HtmlElement], element: &HtmlElement) -> String {
+    if let Some(labelled_by) = element.get_attribute("aria-labelledby") {
+        let name = labelled_by
+            .split_whitespace()
+            .filter_map(|id| by_id(elements, id))
+            .map(|label| text(&label))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if !name.is_empty() {
+            return name;
+        }
+    }
+
+    if let Some(label) = element.get_attribute("aria-label")
+        && !label.trim().is_empty()
+    {
+        return label;
+    }
+
+    let id = element.id();
+    if !id.is_empty()
+        && let Some(label) = elements.iter().find(|label| {
+            label.tag_name().eq_ignore_ascii_case("label")
+                && label.get_attribute("for").as_deref() == Some(&id)
+        })
+    {
+        return text(label);
+    }
+
+    if let Ok(Some(label)) = element.closest("label") {
+        return text(&label);
+    }
+
+    let tag = element.tag_name().to_lowercase();
+    let from_content = match tag.as_str() {
+        "input" | "select" | "textarea" => String::new(),
+        "img" => element.get_attribute("alt").unwrap_or_default(),
+        _ => {
+            let images = descendants(element)
+                .iter()
+                .filter_map(|image| image.get_attribute("alt"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!("{} {images}", text(element)).trim().to_string()
+        }
+    };
+
+    if !from_content.is_empty() {
+        return from_content;
+    }
+
+    element.get_attribute("title").unwrap_or_default()
+}
+
+/// Returns `true` if the element and its descendants are hidden from the assistive technologies
+fn is_hidden_from_assistive_technologies(element: &HtmlElement) -> bool {
+    element
+        .closest("[aria-hidden=\"true\"]")
+        .ok()
+        .flatten()
+        .is_some()
+}
+
+/// Returns the background color visible behind the element
+///
+/// Semi-transparent backgrounds of the ancestors are painted over each other,
+/// starting from the white page.
+fn background(element: &Element) -> Rgba {
+    let mut layers = Vec::new();
+    let mut current = Some(element.clone());
+
+    while let Some(element) = current {
+        if let Some(color) =
+            computed(&element, "background-color").and_then(|color| Rgba::parse(&color))
+            && color.a > 0.0
+        {
+            layers.push(color);
+            if color.a >= 1.0 {
+                break;
+            }
+        }
+        current = element.parent_element();
+    }
+
+    layers
+        .into_iter()
+        .rev()
+        .fold(WHITE, |background, layer| layer.over(background))
+}
+
+/// Checks the images for the `alt` attribute
+fn check_image_alt(elements: &[HtmlElement], violations: &mut Vec<Violation>) {
+    for element in elements {
+        if element.tag_name().eq_ignore_ascii_case("img")
+            && !element.has_attribute("alt")
+            && !matches!(role(element).as_deref(), Some("presentation" | "none"))
+        {
+            violations.push(Violation {
+                rule: Rule::ImageAlt,
+                element: element.clone(),
+                message: "Image has no `alt` attribute. Use `alt=\"\"` for the decorative images."
+                    .to_string(),
+            });
+        }
+    }
+}
+
+/// Checks that the form controls have labels
+fn check_form_label(elements: &[HtmlElement], violations: &mut Vec<Violation>) {
+    for element in elements {
+        let tag = element.tag_name().to_lowercase();
+        let input_type = element
+            .get_attribute("type")
+            .map(|input_type| input_type.to_lowercase());
+
+        let is_control = match tag.as_str() {
+            "select" | "textarea" => true,
+            "input" => !matches!(
+                input_type.as_deref(),
+                Some("hidden" | "button" | "submit" | "reset" | "image")
+            ),
+            _ => false,
+        };
+
+        if is_control && accessible_name(elements, element).is_empty() {
+            violations.push(Violation {
+                rule: Rule::FormLabel,
+                element: element.clone(),
+                message: "Form control has no label. Add a `<label for=\"id\">`, `aria-label` or \
+                          `aria-labelledby`."
+                    .to_string(),
+            });
+        }
+    }
+}
+
+/// Checks that the ids are unique
+fn check_duplicate_id(elements: &[HtmlElement], violations: &mut Vec<Violation>) {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for element in elements {
+        let id = element.id();
+        if !id.is_empty() {
+            *counts.entry(id).or_default() += 1;
+        }
+    }
+
+    for element in elements {
+        let id = element.id();
+        if let Some(count) = counts.get(&id)
+            && *count > 1
+        {
+            violations.push(Violation {
+                rule: Rule::DuplicateId,
+                element: element.clone(),
+                message: format!("Id `{id}` is used by {count} elements"),
+            });
+        }
+    }
+}
+
+/// Checks that the `role` attributes contain valid ARIA roles
+fn check_aria_role(elements: &[HtmlElement], violations: &mut Vec<Violation>) {
+    for element in elements {
+        let Some(roles) = element.get_attribute("role") else {
+            continue;
+        };
+
+        let invalid = roles
+            .split_whitespace()
+            .filter(|role| !is_valid_role(role))
+            .map(|role| format!("`{role}`"))
+            .collect::<Vec<_>>();
+
+        if roles.trim().is_empty() || !invalid.is_empty() {
+            violations.push(Violation {
+                rule: Rule::AriaRole,
+                element: element.clone(),
+                message: if invalid.is_empty() {
+                    "Role is empty".to_string()
+                } else {
+                    format!("Invalid ARIA role {}", invalid.join(", "))
+                },
+            });
+        }
+    }
+}
+
+/// Checks that the buttons have accessible names
+fn check_button_name(elements: &[HtmlElement], violations: &mut Vec<Violation>) {
+    for element in elements {
+        let tag = element.tag_name().to_lowercase();
+        let input_type = element
+            .get_attribute("type")
+            .map(|input_type| input_type.to_lowercase());
+
+        let name = match (tag.as_str(), input_type.as_deref()) {
+            // Submit and reset buttons have the default names provided by the browser
+            ("input", Some("submit" | "reset")) => continue,
+            ("input", Some("button")) => element.get_attribute("value").unwrap_or_default(),
+            ("input", Some("image")) => element.get_attribute("alt").unwrap_or_default(),
+            _ if role(element).as_deref() == Some("button") => accessible_name(elements, element),
+            _ => continue,
+        };
+
+        let title = element.get_attribute("title").unwrap_or_default();
+
+        if name.trim().is_empty() && title.trim().is_empty() {
+            violations.push(Violation {
+                rule: Rule::ButtonName,
+                element: element.clone(),
+                message: "Button has no accessible name. Add a text, `aria-label` or `title`."
+                    .to_string(),
+            });
+        }
+    }
+}
+
+/// Checks the contrast between the text and its background
+fn check_color_contrast(elements: &[HtmlElement], violations: &mut Vec<Violation>) {
+    for element in elements {
+        if own_text(element).is_empty() || !is_visible(element) {
+            continue;
+        }
+
+        let Some(color) = computed(element, "color").and_then(|color| Rgba::parse(&color)) else {
+            continue;
+        };
+
+        let font_size = computed(element, "font-size")
+            .and_then(|size| size.trim_end_matches("px").parse::<f64>().ok())
+            .unwrap_or(16.0);
+        let font_weight = computed(element, "font-weight")
+            .and_then(|weight| weight.parse::<u32>().ok())
+            .unwrap_or(400);
+
+        let background = background(element);
+        let ratio = contrast_ratio(color.over(background), background);
+        let required = required_contrast(font_size, font_weight);
+
+        if ratio < required {
+            violations.push(Violation {
+                rule: Rule::ColorContrast,
+                element: element.clone(),
+                message: format!(
+                    "Contrast ratio is {ratio:.2}:1, but at least {required}:1 is required"
+                ),
+            });
+        }
+    }
+}
+
+/// Audits the content of the canvas
+///
+/// Returns the problems found by all of the [rules][Rule]. Elements hidden from
+/// the assistive technologies with `aria-hidden="true"` are skipped.
+pub fn audit(canvas: &HtmlElement) -> Vec<Violation> {
+    let elements = descendants(canvas)
+        .into_iter()
+        .filter(|element| !is_hidden_from_assistive_technologies(element))
+        .collect::<Vec<_>>();
+
+    let mut violations = Vec::new();
+
+    check_image_alt(&elements, &mut violations);
+    check_form_label(&elements, &mut violations);
+    check_duplicate_id(&elements, &mut violations);
+    check_aria_role(&elements, &mut violations);
+    check_button_name(&elements, &mut violations);
+    check_color_contrast(&elements, &mut violations);
+
+    violations
+}
+
+/// Checks that the accessibility audit of the canvas doesn't find any problem
+///
+/// ```rust,ignore
+/// play::<MyStory>("Story is accessible")
+///     .next("Audit the canvas", |ctx| assert_accessible(ctx.canvas()))
+/// ```
+///
+/// # Errors
+///
+/// Fails with the list of the problems found by the [audit].
+pub fn assert_accessible(canvas: &HtmlElement) -> StepResult {
+    let violations = audit(canvas);
+
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    let details = violations
+        .iter()
+        .map(|violation| format!("- {violation}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Err(
+        StepFailure::new(format!("Found {} accessibility problems", violations.len()))
+            .with_details(details),
+    )
+}
@@ -0,0 +1,240 @@
+//! Assertions which produce readable failures for the steps
+//!
+
+use std::fmt::Display;
+
+use leptos::prelude::window;
+use leptos::wasm_bindgen::JsCast;
+use leptos::web_sys::Element;
+use leptos::web_sys::HtmlElement;
+use leptos::web_sys::HtmlInputElement;
+use leptos::web_sys::HtmlSelectElement;
+use leptos::web_sys::HtmlTextAreaElement;
+
+use super::By;
+use super::markup;
+use super::normalize_text;
+use super::query_all;
+use crate::StepFailure;
+use crate::StepResult;
+
+/// Returns a short description of the element, like `<button data-testid="add">`
+pub(crate) fn describe(element: &Element) -> String {
+    let tag = element.tag_name().to_lowercase();
+    let attributes = ["data-testid", "id", "role", "name"]
+        .into_iter()
+        .filter_map(|name| {
+            element
+                .get_attribute(name)
+                .map(|value| format!(" {name}=\"{value}\""))
+        })
+        .collect::<String>();
+
+    format!("<{tag}{attributes}>")
+}
+
+/// Checks if the `actual` value is equal to the `expected` one
+///
+/// ```rust,ignore
+/// assert_equal("Counter has a wrong value", 5, story.counter.get_untracked())?;
+/// ```
+///
+/// # Errors
+///
+/// Fails with the `message` and both values if they are not equal.
+pub fn assert_equal<T>(message: &str, expected: T, actual: T) -> StepResult
+where
+    T: PartialEq + Display,
+{
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(StepFailure::mismatch(message, expected, actual))
+    }
+}
+
+/// Checks the text of the element
+///
+/// Whitespace in both texts is collapsed before they are compared.
+///
+/// # Errors
+///
+/// Fails if the text of the element is different than the `expected` one.
+pub fn assert_text(element: &HtmlElement, expected: &str) -> StepResult {
+    let actual = normalize_text(&element.inner_text());
+    let expected = normalize_text(expected);
+
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(StepFailure::mismatch(
+            format!("{} has a wrong text", describe(element)),
+            expected,
+            actual,
+        ))
+    }
+}
+
+/// Checks if the text of the element contains the `expected` text
+///
+/// # Errors
+///
+/// Fails if the `expected` text is not a part of the element's text.
+pub fn assert_text_contains(element: &HtmlElement, expected: &str) -> StepResult {
+    let actual = normalize_text(&element.inner_text());
+    let expected = normalize_text(expected);
+
+    if actual.contains(&expected) {
+        Ok(())
+    } else {
+        Err(StepFailure::mismatch(
+            format!("{} doesn't contain the text", describe(element)),
+            expected,
+            actual,
+        ))
+    }
+}
+
+/// Checks the value of the attribute
+///
+/// Use `None` as the `expected` value to check that the attribute is not set.
+///
+/// # Errors
+///
+/// Fails if the attribute has a different value than expected.
+pub fn assert_attribute(element: &Element, name: &str, expected: Option<&str>) -> StepResult {
+    let actual = element.get_attribute(name);
+
+    if actual.as_deref() == expected {
+        Ok(())
+    } else {
+        Err(StepFailure::mismatch(
+            format!(
+                "Attribute `{name}` of {} has a wrong value",
+                describe(element)
+            ),
+            expected.unwrap_or("<not set>"),
+            actual.as_deref().unwrap_or("<not set>"),
+        ))
+    }
+}
+
+/// Checks the value of the `<input>`, `<textarea>` or `<select>`
+///
+/// # Errors
+///
+/// Fails if the element is not a form control or if it has a wrong value.
+pub fn assert_value(element: &Element, expected: &str) -> StepResult {
+    let actual = if let Some(input) = element.dyn_ref::<HtmlInputElement>() {
+        input.value()
+    } else if let Some(textarea) = element.dyn_ref::<HtmlTextAreaElement>() {
+        textarea.value()
+    } else if let Some(select) = element.dyn_ref::<HtmlSelectElement>() {
+        select.value()
+    } else {
+        return Err(StepFailure::new(format!(
+            "{} is not an input, textarea or select",
+            describe(element)
+        )));
+    };
+
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(StepFailure::mismatch(
+            format!("{} has a wrong value", describe(element)),
+            expected,
+            actual,
+        ))
+    }
+}
+
+/// Checks if the checkbox or radio button is checked
+///
+/// # Errors
+///
+/// Fails if the element is not an `<input>` or if it's in a different state than expected.
+pub fn assert_checked(element: &Element, expected: bool) -> StepResult {
+    let Some(input) = element.dyn_ref::<HtmlInputElement>() else {
+        return Err(StepFailure::new(format!(
+            "{} is not an input",
+            describe(element)
+        )));
+    };
+
+    assert_equal(
+        &format!("{} has a wrong checked state", describe(element)),
+        expected,
+        input.checked(),
+    )
+}
+
+/// Returns `true` if the element is visible to the user
+pub(crate) fn is_visible(element: &HtmlElement) -> bool {
+    if element.closest("[hidden]").ok().flatten().is_some() {
+        return false;
+    }
+
+    if element.offset_width() == 0 && element.offset_height() == 0 {
+        return false;
+    }
+
+    let visibility = window()
+        .get_computed_style(element)
+        .ok()
+        .flatten()
+        .and_then(|style| style.get_property_value("visibility").ok());
+
+    !matches!(visibility.as_deref(), Some("hidden" | "collapse"))
+}
+
+/// Checks if the element is visible to the user
+///
+/// # Errors
+///
+/// Fails if the element is hidden, has no size or its `visibility` is `hidden`.
+pub fn assert_visible(element: &HtmlElement) -> StepResult {
+    if is_visible(element) {
+        Ok(())
+    } else {
+        Err(StepFailure::new(format!(
+            "{} is not visible",
+            describe(element)
+        )))
+    }
+}
+
+/// Checks if the element is hidden from the user
+///
+/// # Errors
+///
+/// Fails if the element is visible.
+pub fn assert_hidden(element: &HtmlElement) -> StepResult {
+    if is_visible(element) {
+        Err(StepFailure::new(format!(
+            "{} is visible",
+            describe(element)
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks that no element inside of the canvas matches the query
+///
+/// # Errors
+///
+/// Fails if any element matches the query.
+pub fn assert_absent(canvas: &HtmlElement, by: By<'_>) -> StepResult {
+    let found = query_all(canvas, by);
+
+    if found.is_empty() {
+        Ok(())
+    } else {
+        Err(StepFailure::new(format!(
+            "Found {} with {by}, but it should be absent",
+            describe(&found[0])
+        ))
+        .with_details(markup(canvas)))
+    }
+}
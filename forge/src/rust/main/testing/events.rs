@@ -0,0 +1,610 @@
+//! Simulation of the user interactions
+//!
+//! Every function dispatches the whole sequence of events which the browser would
+//! dispatch if the real user did the same thing. For example [click] dispatches
+//! `pointerdown`, `mousedown`, `focus`, `pointerup`, `mouseup` and `click`, so the
+//! components listening to any of these events will see them.
+
+use leptos::prelude::window;
+use leptos::wasm_bindgen::JsCast;
+use leptos::wasm_bindgen::JsValue;
+use leptos::web_sys::DataTransfer;
+use leptos::web_sys::Element;
+use leptos::web_sys::Event;
+use leptos::web_sys::EventInit;
+use leptos::web_sys::File;
+use leptos::web_sys::FilePropertyBag;
+use leptos::web_sys::HtmlElement;
+use leptos::web_sys::HtmlInputElement;
+use leptos::web_sys::HtmlOptionElement;
+use leptos::web_sys::HtmlSelectElement;
+use leptos::web_sys::HtmlTextAreaElement;
+use leptos::web_sys::InputEvent;
+use leptos::web_sys::InputEventInit;
+use leptos::web_sys::KeyboardEvent;
+use leptos::web_sys::KeyboardEventInit;
+use leptos::web_sys::MouseEvent;
+use leptos::web_sys::MouseEventInit;
+use leptos::web_sys::PointerEvent;
+use leptos::web_sys::PointerEventInit;
+use leptos::web_sys::js_sys::Array;
+use utils_leptos::stores::stored_ref::StoredRef;
+
+use super::describe;
+use crate::StepFailure;
+use crate::StepResult;
+use crate::runner::mark_target;
+
+/// Dispatches the `event` on the `target`
+///
+/// Returns `false` if any of the handlers has called `Event::prevent_default()`.
+fn dispatch(target: &Element, event: &Event) -> Result<bool, StepFailure> {
+    mark_target(target);
+
+    StoredRef::new(target.clone())
+        .dispatch_event(event)
+        .map_err(|_| {
+            StepFailure::new(format!(
+                "Unable to dispatch the `{}` event on {}",
+                event.type_(),
+                describe(target)
+            ))
+        })
+}
+
+/// Returns the failure for the event which couldn't be created
+fn creation_failure(kind: &str) -> StepFailure {
+    StepFailure::new(format!("Unable to create the `{kind}` event"))
+}
+
+/// Returns `true` if the event of the given kind bubbles up the DOM tree
+fn bubbles(kind: &str) -> bool {
+    !matches!(
+        kind,
+        "mouseenter" | "mouseleave" | "pointerenter" | "pointerleave" | "focus" | "blur"
+    )
+}
+
+/// Dispatches the mouse event
+///
+/// - `detail` - number of clicks in the short succession
+fn mouse(target: &Element, kind: &str, detail: i32) -> Result<bool, StepFailure> {
+    let init = MouseEventInit::new();
+    init.set_bubbles(bubbles(kind));
+    init.set_cancelable(true);
+    init.set_composed(true);
+    init.set_detail(detail);
+    init.set_view(Some(&window()));
+
+    let event = MouseEvent::new_with_mouse_event_init_dict(kind, &init)
+        .map_err(|_| creation_failure(kind))?;
+
+    dispatch(target, &event)
+}
+
+/// Dispatches the pointer event of the primary mouse pointer
+fn pointer(target: &Element, kind: &str) -> Result<bool, StepFailure> {
+    let init = PointerEventInit::new();
+    init.set_bubbles(bubbles(kind));
+    init.set_cancelable(true);
+    init.set_composed(true);
+    init.set_view(Some(&window()));
+    init.set_pointer_id(1);
+    init.set_pointer_type("mouse");
+    init.set_is_primary(true);
+
+    let event =
+        PointerEvent::new_with_event_init_dict(kind, &init).map_err(|_| creation_failure(kind))?;
+
+    dispatch(target, &event)
+}
+
+/// Dispatches the keyboard event
+fn keyboard(target: &Element, kind: &str, key: &str) -> Result<bool, StepFailure> {
+    let init = KeyboardEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    init.set_composed(true);
+    init.set_key(key);
+    init.set_code(&key_code(key));
+
+    let event = KeyboardEvent::new_with_keyboard_event_init_dict(kind, &init)
+        .map_err(|_| creation_failure(kind))?;
+
+    dispatch(target, &event)
+}
+
+/// Dispatches the `beforeinput` or `input` event for the inserted `data`
+///
+/// - `input_type` - kind of the edit, like `insertText` or `deleteContentBackward`
+fn input(
+    target: &Element,
+    kind: &str,
+    data: Option<&str>,
+    input_type: &str,
+) -> Result<bool, StepFailure> {
+    let init = InputEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(kind == "beforeinput");
+    init.set_composed(true);
+    init.set_data(data);
+    init.set_input_type(input_type);
+
+    let event =
+        InputEvent::new_with_event_init_dict(kind, &init).map_err(|_| creation_failure(kind))?;
+
+    dispatch(target, &event)
+}
+
+/// Dispatches the simple event, like `change`
+fn simple(target: &Element, kind: &str) -> Result<bool, StepFailure> {
+    let init = EventInit::new();
+    init.set_bubbles(true);
+
+    let event = Event::new_with_event_init_dict(kind, &init).map_err(|_| creation_failure(kind))?;
+
+    dispatch(target, &event)
+}
+
+/// Returns the value of the `code` property of the keyboard event for the given `key`
+///
+/// `code` describes the physical key on the US keyboard, for example `KeyA` for
+/// the `a` and `A` keys, or `Digit1` for `1`.
+pub(crate) fn key_code(key: &str) -> String {
+    let mut chars = key.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) if ch.is_ascii_alphabetic() => format!("Key{}", ch.to_ascii_uppercase()),
+        (Some(ch), None) if ch.is_ascii_digit() => format!("Digit{ch}"),
+        (Some(' '), None) => "Space".to_string(),
+        (Some(ch), None) => match ch {
+            '-' => "Minus",
+            '=' | '+' => "Equal",
+            ',' => "Comma",
+            '.' => "Period",
+            '/' => "Slash",
+            ';' => "Semicolon",
+            '\'' => "Quote",
+            '[' => "BracketLeft",
+            ']' => "BracketRight",
+            '\\' => "Backslash",
+            '`' => "Backquote",
+            _ => "",
+        }
+        .to_string(),
+        _ => key.to_string(),
+    }
+}
+
+/// Returns `true` if the key produces a character, or the `Enter` key
+fn has_keypress(key: &str) -> bool {
+    key.chars().count() == 1 || key == "Enter"
+}
+
+/// Fails if the element is disabled
+fn ensure_enabled(target: &Element) -> StepResult {
+    let disabled = target.has_attribute("disabled")
+        || target.get_attribute("aria-disabled").as_deref() == Some("true");
+
+    if disabled {
+        Err(StepFailure::new(format!(
+            "{} is disabled, so the user can't interact with it",
+            describe(target)
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Moves the focus to the `target`
+///
+/// Browser dispatches the `focus`, `focusin`, `blur` and `focusout` events
+/// by itself, while the focus is moved.
+///
+/// # Errors
+///
+/// Fails if the `target` is not an html element.
+pub fn focus(target: &Element) -> StepResult {
+    let Some(element) = target.dyn_ref::<HtmlElement>() else {
+        return Err(StepFailure::new(format!(
+            "{} can't be focused",
+            describe(target)
+        )));
+    };
+
+    element
+        .focus()
+        .map_err(|_| StepFailure::new(format!("Unable to focus {}", describe(target))))
+}
+
+/// Removes the focus from the `target`
+///
+/// # Errors
+///
+/// Fails if the `target` is not an html element.
+pub fn blur(target: &Element) -> StepResult {
+    let Some(element) = target.dyn_ref::<HtmlElement>() else {
+        return Err(StepFailure::new(format!(
+            "{} can't be blurred",
+            describe(target)
+        )));
+    };
+
+    element
+        .blur()
+        .map_err(|_| StepFailure::new(format!("Unable to blur {}", describe(target))))
+}
+
+/// Moves the mouse pointer over the `target`
+///
+/// Dispatches `pointerover`, `pointerenter`, `mouseover`, `mouseenter`,
+/// `pointermove` and `mousemove` events.
+///
+/// # Errors
+///
+/// Fails if any of the events can't be dispatched.
+pub fn hover(target: &Element) -> StepResult {
+    pointer(target, "pointerover")?;
+    pointer(target, "pointerenter")?;
+    mouse(target, "mouseover", 0)?;
+    mouse(target, "mouseenter", 0)?;
+    pointer(target, "pointermove")?;
+    mouse(target, "mousemove", 0)?;
+
+    Ok(())
+}
+
+/// Moves the mouse pointer away from the `target`
+///
+/// Dispatches `pointerout`, `pointerleave`, `mouseout` and `mouseleave` events.
+///
+/// # Errors
+///
+/// Fails if any of the events can't be dispatched.
+pub fn unhover(target: &Element) -> StepResult {
+    pointer(target, "pointerout")?;
+    pointer(target, "pointerleave")?;
+    mouse(target, "mouseout", 0)?;
+    mouse(target, "mouseleave", 0)?;
+
+    Ok(())
+}
+
+/// Presses and releases the primary mouse button over the `target`
+///
+/// - `detail` - number of clicks in the short succession
+fn press(target: &Element, detail: i32) -> StepResult {
+    let pointer_down = pointer(target, "pointerdown")?;
+    let mouse_down = pointer_down && mouse(target, "mousedown", detail)?;
+
+    // browser doesn't move the focus if the `mousedown` was cancelled
+    if mouse_down && target.dyn_ref::<HtmlElement>().is_some() {
+        focus(target)?;
+    }
+
+    pointer(target, "pointerup")?;
+    if pointer_down {
+        mouse(target, "mouseup", detail)?;
+    }
+    mouse(target, "click", detail)?;
+
+    Ok(())
+}
+
+/// Clicks the `target` with the primary mouse button
+///
+/// Dispatches the hover events, and then `pointerdown`, `mousedown`, `focus`,
+/// `pointerup`, `mouseup` and `click`.
+///
+/// ```rust,ignore
+/// play::<CounterStory>("Counter is increased")
+///     .next("Click the + button", |ctx| {
+///         click(&get_by_text(ctx.canvas(), "+")?)
+///     })
+/// ```
+///
+/// # Errors
+///
+/// Fails if the `target` is disabled, or if any of the events can't be dispatched.
+pub fn click(target: &Element) -> StepResult {
+    ensure_enabled(target)?;
+    hover(target)?;
+    press(target, 1)
+}
+
+/// Double clicks the `target` with the primary mouse button
+///
+/// Dispatches the events of two clicks followed by the `dblclick` event.
+///
+/// # Errors
+///
+/// Fails if the `target` is disabled, or if any of the events can't be dispatched.
+pub fn dblclick(target: &Element) -> StepResult {
+    ensure_enabled(target)?;
+    hover(target)?;
+    press(target, 1)?;
+    press(target, 2)?;
+    mouse(target, "dblclick", 2)?;
+
+    Ok(())
+}
+
+/// Presses and releases the key while the `target` has the focus
+///
+/// `key` is the value of the `KeyboardEvent.key`, like `a`, `Enter` or `Escape`.
+/// Dispatches `keydown`, `keypress` (for the keys producing a character and the
+/// `Enter` key) and `keyup`.
+///
+/// `Backspace` and `Delete` edit the value of the `<input>` or `<textarea>` the
+/// same way as in the browser: they remove the selected text, or the character
+/// before or after the cursor, and dispatch `beforeinput` and `input`. Other keys
+/// don't change the value of the inputs, use the [type_text] for that.
+///
+/// # Errors
+///
+/// Fails if any of the events can't be dispatched.
+pub fn press_key(target: &Element, key: &str) -> StepResult {
+    if keyboard(target, "keydown", key)? {
+        if has_keypress(key) {
+            keyboard(target, "keypress", key)?;
+        }
+
+        if let Some(input_type) = delete_input_type(key)
+            && let Some(field) = TextField::of(target)
+            && input(target, "beforeinput", None, input_type)?
+            && field.erase(key == "Backspace")
+        {
+            input(target, "input", None, input_type)?;
+        }
+    }
+    keyboard(target, "keyup", key)?;
+
+    Ok(())
+}
+
+/// Returns the `inputType` of the edit made by the `Backspace` or `Delete` key
+fn delete_input_type(key: &str) -> Option<&'static str> {
+    match key {
+        "Backspace" => Some("deleteContentBackward"),
+        "Delete" => Some("deleteContentForward"),
+        _ => None,
+    }
+}
+
+/// The `<input>` or `<textarea>` which value can be edited
+enum TextField {
+    /// The `<input>`
+    Input(HtmlInputElement),
+    /// The `<textarea>`
+    TextArea(HtmlTextAreaElement),
+}
+
+impl TextField {
+    /// Returns the field if the `target` is an `<input>` or `<textarea>`
+    fn of(target: &Element) -> Option<Self> {
+        if let Some(input) = target.dyn_ref::<HtmlInputElement>() {
+            Some(Self::Input(input.clone()))
+        } else {
+            target
+                .dyn_ref::<HtmlTextAreaElement>()
+                .map(|textarea| Self::TextArea(textarea.clone()))
+        }
+    }
+
+    /// Removes the selected text, or the character before (`backward`) or after the cursor
+    ///
+    /// Returns `false` if nothing was removed. Fields which don't expose the
+    /// cursor, like `<input type="number">`, are edited at the end of the value.
+    fn erase(&self, backward: bool) -> bool {
+        let (value, start, end) = match self {
+            Self::Input(input) => (
+                input.value(),
+                input.selection_start().ok().flatten(),
+                input.selection_end().ok().flatten(),
+            ),
+            Self::TextArea(textarea) => (
+                textarea.value(),
+                textarea.selection_start().ok().flatten(),
+                textarea.selection_end().ok().flatten(),
+            ),
+        };
+
+        let length = value.encode_utf16().count() as u32;
+        let start = start.unwrap_or(length);
+        let end = end.unwrap_or(start);
+
+        let Some((value, cursor)) = erased(&value, start, end, backward) else {
+            return false;
+        };
+
+        // Not every input type supports the selection, so the failure to move the cursor is ignored
+        match self {
+            Self::Input(input) => {
+                input.set_value(&value);
+                let _ = input.set_selection_range(cursor, cursor);
+            }
+            Self::TextArea(textarea) => {
+                textarea.set_value(&value);
+                let _ = textarea.set_selection_range(cursor, cursor);
+            }
+        }
+
+        true
+    }
+}
+
+/// Returns the `value` after `Backspace` (`backward`) or `Delete`, and the new cursor position
+///
+/// `start` and `end` are the bounds of the selection, in the UTF-16 code units
+/// like in the browser. The selected text is removed, if nothing is selected the
+/// character before or after the cursor is removed. Returns `None` if there is
+/// nothing to remove.
+pub(crate) fn erased(value: &str, start: u32, end: u32, backward: bool) -> Option<(String, u32)> {
+    let units = value.encode_utf16().collect::<Vec<_>>();
+    let length = units.len();
+    let mut start = (start as usize).min(length);
+    let mut end = (end as usize).clamp(start, length);
+
+    if start == end {
+        let chars = char::decode_utf16(units.iter().copied())
+            .map(|ch| ch.map_or(1, char::len_utf16))
+            .scan(0, |offset, width| {
+                *offset += width;
+                Some(*offset - width..*offset)
+            })
+            .collect::<Vec<_>>();
+
+        let removed = if backward {
+            chars.iter().rev().find(|ch| ch.end <= start)
+        } else {
+            chars.iter().find(|ch| ch.start >= start)
+        }?;
+
+        start = removed.start;
+        end = removed.end;
+    }
+
+    let mut result = units[..start].to_vec();
+    result.extend_from_slice(&units[end..]);
+
+    Some((String::from_utf16_lossy(&result), start as u32))
+}
+
+/// Appends the character to the value of the `<input>` or `<textarea>`
+fn append(target: &Element, ch: char) -> StepResult {
+    if let Some(input) = target.dyn_ref::<HtmlInputElement>() {
+        input.set_value(&format!("{}{ch}", input.value()));
+    } else if let Some(textarea) = target.dyn_ref::<HtmlTextAreaElement>() {
+        textarea.set_value(&format!("{}{ch}", textarea.value()));
+    } else {
+        return Err(StepFailure::new(format!(
+            "{} is not an input or textarea",
+            describe(target)
+        )));
+    }
+
+    Ok(())
+}
+
+/// Types the `text` into the `<input>` or `<textarea>`
+///
+/// The `target` is clicked first, so it gets the focus. Then for every character
+/// `keydown`, `keypress`, `beforeinput`, `input` and `keyup` events are dispatched.
+/// If the `keydown` or `beforeinput` event is cancelled, the character is not
+/// inserted. After the last character the `change` event is dispatched, as if
+/// the user has committed the value.
+///
+/// # Errors
+///
+/// Fails if the `target` is disabled, it's not an input or textarea, or if any
+/// of the events can't be dispatched.
+pub fn type_text(target: &Element, text: &str) -> StepResult {
+    click(target)?;
+
+    let mut buf = [0; 4];
+    for ch in text.chars() {
+        let key: &str = ch.encode_utf8(&mut buf);
+
+        if keyboard(target, "keydown", key)? {
+            keyboard(target, "keypress", key)?;
+
+            if input(target, "beforeinput", Some(key), "insertText")? {
+                append(target, ch)?;
+                input(target, "input", Some(key), "insertText")?;
+            }
+        }
+
+        keyboard(target, "keyup", key)?;
+    }
+
+    simple(target, "change")?;
+
+    Ok(())
+}
+
+/// Selects the option of the `<select>`
+///
+/// Option is matched by its value or by its text. Dispatches the `input` and
+/// `change` events after the option is selected.
+///
+/// # Errors
+///
+/// Fails if the `target` is not a select, it's disabled, there is no such
+/// option, or if any of the events can't be dispatched.
+pub fn select_option(target: &Element, option: &str) -> StepResult {
+    let Some(select) = target.dyn_ref::<HtmlSelectElement>() else {
+        return Err(StepFailure::new(format!(
+            "{} is not a select",
+            describe(target)
+        )));
+    };
+
+    ensure_enabled(target)?;
+    click(target)?;
+
+    let options = select.get_elements_by_tag_name("option");
+    let found = (0..options.length())
+        .filter_map(|idx| options.item(idx))
+        .filter_map(|option| option.dyn_into::<HtmlOptionElement>().ok())
+        .find(|candidate| candidate.value() == option || candidate.text().trim() == option);
+
+    let Some(found) = found else {
+        return Err(StepFailure::new(format!(
+            "{} has no option `{option}`",
+            describe(target)
+        )));
+    };
+
+    found.set_selected(true);
+
+    simple(target, "input")?;
+    simple(target, "change")?;
+
+    Ok(())
+}
+
+/// Uploads the file to the `<input type="file">`
+///
+/// File is created with the given `name`, `content` and the `mime` type. Dispatches
+/// the `input` and `change` events after the file is set.
+///
+/// ```rust,ignore
+/// upload_file(&get_by_label_text(ctx.canvas(), "Avatar")?, "avatar.svg", "<svg />", "image/svg+xml")?;
+/// ```
+///
+/// # Errors
+///
+/// Fails if the `target` is not an input, it's disabled, the file can't be created,
+/// or if any of the events can't be dispatched.
+pub fn upload_file(target: &Element, name: &str, content: &str, mime: &str) -> StepResult {
+    let Some(input) = target.dyn_ref::<HtmlInputElement>() else {
+        return Err(StepFailure::new(format!(
+            "{} is not an input",
+            describe(target)
+        )));
+    };
+
+    ensure_enabled(target)?;
+
+    let parts = Array::of1(&JsValue::from_str(content));
+    let properties = FilePropertyBag::new();
+    properties.set_type(mime);
+
+    let file = File::new_with_str_sequence_and_options(&parts, name, &properties)
+        .map_err(|_| StepFailure::new(format!("Unable to create the file `{name}`")))?;
+
+    let transfer =
+        DataTransfer::new().map_err(|_| StepFailure::new("Unable to create the data transfer"))?;
+    transfer.items().add_with_file(&file).map_err(|_| {
+        StepFailure::new(format!(
+            "Unable to add the file `{name}` to the data transfer"
+        ))
+    })?;
+
+    input.set_files(transfer.files().as_ref());
+
+    simple(target, "input")?;
+    simple(target, "change")?;
+
+    Ok(())
+}
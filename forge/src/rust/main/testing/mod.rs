@@ -0,0 +1,77 @@
+//! Helpers for writing the steps of the plays
+//!
+//! Steps receive the [StepContext][crate::StepContext] with the canvas in which
+//! the story is drawn. This module provides the queries which find the elements
+//! inside of the canvas and the assertions which produce readable
+//! [StepFailure][crate::StepFailure]s.
+//!
+//! ```rust,ignore
+//! use forge::play;
+//! use forge::testing::*;
+//!
+//! play::<CounterStory>("Counter shows the message")
+//!     .next("Check the message", |ctx| {
+//!         let message = get_by_test_id(ctx.canvas(), "message")?;
+//!         assert_text(&message, "Value has crossed the threshold")
+//!     })
+//! ```
+//!
+//! Queries come in a few flavours
+//!
+//! - [get] - returns exactly one element or fails
+//! - [query] - returns at most one element, `None` if there is no such element
+//! - [get_all] - returns at least one element or fails
+//! - [query_all] - returns all of the matching elements
+//!
+//! Elements can be found [By] test id, role, label and text. There are also the
+//! shortcuts like [get_by_test_id] for the most common queries, which are also
+//! available as the methods of the context, like `ctx.get_by_test_id("message")`.
+//!
+//! The [audit] checks the canvas for the common accessibility problems, and the
+//! [assert_accessible] fails the step if there are any.
+
+mod accessibility;
+mod assertions;
+mod events;
+mod queries;
+#[cfg(test)]
+mod tests;
+
+pub use accessibility::Rule;
+pub use accessibility::Violation;
+pub use accessibility::assert_accessible;
+pub use accessibility::audit;
+pub use assertions::assert_absent;
+pub use assertions::assert_attribute;
+pub use assertions::assert_checked;
+pub use assertions::assert_equal;
+pub use assertions::assert_hidden;
+pub use assertions::assert_text;
+pub use assertions::assert_text_contains;
+pub use assertions::assert_value;
+pub use assertions::assert_visible;
+pub(crate) use assertions::describe;
+pub use events::blur;
+pub use events::click;
+pub use events::dblclick;
+pub use events::focus;
+pub use events::hover;
+pub use events::press_key;
+pub use events::select_option;
+pub use events::type_text;
+pub use events::unhover;
+pub use events::upload_file;
+pub use queries::By;
+pub(crate) use queries::find_all;
+pub use queries::get;
+pub use queries::get_all;
+pub use queries::get_by_label_text;
+pub use queries::get_by_role;
+pub use queries::get_by_test_id;
+pub use queries::get_by_text;
+pub(crate) use queries::markup;
+pub use queries::normalize_text;
+pub(crate) use queries::own_text;
+pub use queries::query;
+pub use queries::query_all;
+pub(crate) use queries::role;
@@ -0,0 +1,386 @@
+//! Queries which find the elements inside of the canvas
+//!
+
+use std::fmt::Display;
+
+use leptos::wasm_bindgen::JsCast;
+use leptos::web_sys::Element;
+use leptos::web_sys::HtmlElement;
+use leptos::web_sys::Node;
+
+use crate::StepFailure;
+use crate::runner::mark_target;
+use crate::runner::mark_targets;
+
+/// How many characters of the canvas markup are shown in the failure details
+const MAX_MARKUP_LENGTH: usize = 2000;
+
+/// Describes how the element should be found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum By<'a> {
+    /// Value of the `data-testid` attribute, as set by the [test_id][crate::test_id]
+    TestId(&'a str),
+    /// Value of the `role` attribute, or the implicit role of the element
+    ///
+    /// For example `<button>` has an implicit role `button` and `<h2>` has an
+    /// implicit role `heading`.
+    Role(&'a str),
+    /// Text of the form control's `<label>`, `aria-label` or `aria-labelledby`
+    Label(&'a str),
+    /// Text of the element
+    ///
+    /// Only the text nodes which are direct children of the element are taken
+    /// into account, so the query finds the element which shows the text instead
+    /// of all of its ancestors.
+    Text(&'a str),
+}
+
+impl Display for By<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use By::*;
+        match self {
+            TestId(id) => write!(f, "test id `{id}`"),
+            Role(role) => write!(f, "role `{role}`"),
+            Label(label) => write!(f, "label `{label}`"),
+            Text(text) => write!(f, "text `{text}`"),
+        }
+    }
+}
+
+/// Collapses the whitespace in the text, so it can be compared with the expected value
+///
+/// Leading and trailing whitespace is removed, and every other run of whitespace
+/// is replaced by a single space.
+pub fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Returns the implicit ARIA role of the element
+///
+/// - `tag` - lowercase name of the tag
+/// - `input_type` - lowercase value of the `type` attribute of the `<input>`
+/// - `has_href` - `true` if element has the `href` attribute
+pub(crate) fn implicit_role(
+    tag: &str,
+    input_type: Option<&str>,
+    has_href: bool,
+) -> Option<&'static str> {
+    let role = match tag {
+        "a" | "area" if has_href => "link",
+        "article" => "article",
+        "aside" => "complementary",
+        "button" => "button",
+        "dialog" => "dialog",
+        "form" => "form",
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => "heading",
+        "hr" => "separator",
+        "img" => "img",
+        "input" => match input_type.unwrap_or("text") {
+            "button" | "image" | "reset" | "submit" => "button",
+            "checkbox" => "checkbox",
+            "number" => "spinbutton",
+            "radio" => "radio",
+            "range" => "slider",
+            "search" => "searchbox",
+            "email" | "tel" | "text" | "url" => "textbox",
+            _ => return None,
+        },
+        "li" => "listitem",
+        "main" => "main",
+        "nav" => "navigation",
+        "ol" | "ul" | "menu" => "list",
+        "option" => "option",
+        "progress" => "progressbar",
+        "select" => "combobox",
+        "table" => "table",
+        "tbody" | "thead" | "tfoot" => "rowgroup",
+        "td" => "cell",
+        "textarea" => "textbox",
+        "th" => "columnheader",
+        "tr" => "row",
+        _ => return None,
+    };
+
+    Some(role)
+}
+
+/// Returns the role of the element
+///
+/// Explicit `role` attribute takes precedence over the implicit role of the element.
+pub(crate) fn role(element: &Element) -> Option<String> {
+    if let Some(role) = element.get_attribute("role")
+        && let Some(role) = role.split_whitespace().next()
+    {
+        return Some(role.to_string());
+    }
+
+    let tag = element.tag_name().to_lowercase();
+    let input_type = element
+        .get_attribute("type")
+        .map(|input_type| input_type.to_lowercase());
+
+    implicit_role(&tag, input_type.as_deref(), element.has_attribute("href"))
+        .map(ToString::to_string)
+}
+
+/// Returns the text of the text nodes which are direct children of the `element`
+pub(crate) fn own_text(element: &Element) -> String {
+    let mut text = String::new();
+    let mut child = element.first_child();
+
+    while let Some(node) = child {
+        if node.node_type() == Node::TEXT_NODE
+            && let Some(content) = node.text_content()
+        {
+            text.push_str(&content);
+        }
+        child = node.next_sibling();
+    }
+
+    normalize_text(&text)
+}
+
+/// Returns the normalized text content of the `element`
+pub(crate) fn text(element: &Element) -> String {
+    normalize_text(&element.text_content().unwrap_or_default())
+}
+
+/// Returns all of the descendants of the `root` in the document order
+pub(crate) fn descendants(root: &HtmlElement) -> Vec<HtmlElement> {
+    let collection = root.get_elements_by_tag_name("*");
+
+    (0..collection.length())
+        .filter_map(|idx| collection.item(idx))
+        .filter_map(|element| element.dyn_into::<HtmlElement>().ok())
+        .collect()
+}
+
+/// Returns `true` if the element can be associated with a `<label>`
+fn is_labelable(element: &Element) -> bool {
+    matches!(
+        element.tag_name().to_lowercase().as_str(),
+        "button" | "input" | "meter" | "output" | "progress" | "select" | "textarea"
+    )
+}
+
+/// Finds the form controls labelled with the given `label`
+fn query_all_by_label(root: &HtmlElement, label: &str) -> Vec<HtmlElement> {
+    let label = normalize_text(label);
+    let elements = descendants(root);
+    let mut found: Vec<HtmlElement> = Vec::new();
+
+    let mut push = |element: &HtmlElement| {
+        if !found.contains(element) {
+            found.push(element.clone());
+        }
+    };
+
+    for element in &elements {
+        let tag = element.tag_name().to_lowercase();
+
+        if tag == "label" && text(element) == label {
+            if let Some(target) = element.get_attribute("for") {
+                elements
+                    .iter()
+                    .filter(|control| control.id() == target)
+                    .for_each(&mut push);
+            } else {
+                descendants(element)
+                    .iter()
+                    .filter(|control| is_labelable(control))
+                    .take(1)
+                    .for_each(&mut push);
+            }
+        }
+
+        if element
+            .get_attribute("aria-label")
+            .is_some_and(|aria_label| normalize_text(&aria_label) == label)
+        {
+            push(element);
+        }
+
+        if let Some(labelled_by) = element.get_attribute("aria-labelledby") {
+            let labelled_by = labelled_by
+                .split_whitespace()
+                .filter_map(|id| elements.iter().find(|element| element.id() == id))
+                .map(|element| text(element))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            if labelled_by == label {
+                push(element);
+            }
+        }
+    }
+
+    found
+}
+
+/// Returns all of the elements inside of the `canvas` which match the query
+///
+/// The `canvas` itself is never returned.
+pub fn query_all(canvas: &HtmlElement, by: By<'_>) -> Vec<HtmlElement> {
+    let found = find_all(canvas, by);
+    mark_targets(&found);
+    found
+}
+
+/// Returns all of the elements matching the query without marking them as the targets of the step
+///
+/// Used by the queries which mark only some of the found elements and outside of the steps.
+pub(crate) fn find_all(canvas: &HtmlElement, by: By<'_>) -> Vec<HtmlElement> {
+    use By::*;
+
+    match by {
+        TestId(id) => descendants(canvas)
+            .into_iter()
+            .filter(|element| element.get_attribute("data-testid").as_deref() == Some(id))
+            .collect(),
+        Role(expected) => descendants(canvas)
+            .into_iter()
+            .filter(|element| role(element).as_deref() == Some(expected))
+            .collect(),
+        Label(label) => query_all_by_label(canvas, label),
+        Text(expected) => {
+            let expected = normalize_text(expected);
+            descendants(canvas)
+                .into_iter()
+                .filter(|element| {
+                    !matches!(
+                        element.tag_name().to_lowercase().as_str(),
+                        "script" | "style"
+                    )
+                })
+                .filter(|element| own_text(element) == expected)
+                .collect()
+        }
+    }
+}
+
+/// Returns the element matching the query or `None` if there is no such element
+///
+/// # Errors
+///
+/// Fails if more than one element matches the query.
+pub fn query(canvas: &HtmlElement, by: By<'_>) -> Result<Option<HtmlElement>, StepFailure> {
+    let mut found = find_all(canvas, by);
+
+    if found.len() > 1 {
+        return Err(StepFailure::new(format!(
+            "Found {} elements with {by}, but expected at most one",
+            found.len()
+        ))
+        .with_details(markup(canvas)));
+    }
+
+    let found = found.pop();
+    if let Some(element) = &found {
+        mark_target(element);
+    }
+
+    Ok(found)
+}
+
+/// Returns the only element matching the query
+///
+/// # Errors
+///
+/// Fails if there is no element matching the query, or if there is more than one.
+pub fn get(canvas: &HtmlElement, by: By<'_>) -> Result<HtmlElement, StepFailure> {
+    let found = find_all(canvas, by);
+
+    match found.as_slice() {
+        [] => Err(
+            StepFailure::new(format!("Unable to find an element with {by}"))
+                .with_details(markup(canvas)),
+        ),
+        [element] => {
+            mark_target(element);
+            Ok(element.clone())
+        }
+        _ => Err(StepFailure::new(format!(
+            "Found {} elements with {by}, but expected exactly one",
+            found.len()
+        ))
+        .with_details(markup(canvas))),
+    }
+}
+
+/// Returns all of the elements matching the query
+///
+/// # Errors
+///
+/// Fails if there is no element matching the query.
+pub fn get_all(canvas: &HtmlElement, by: By<'_>) -> Result<Vec<HtmlElement>, StepFailure> {
+    let found = query_all(canvas, by);
+
+    if found.is_empty() {
+        Err(
+            StepFailure::new(format!("Unable to find any element with {by}"))
+                .with_details(markup(canvas)),
+        )
+    } else {
+        Ok(found)
+    }
+}
+
+/// Returns the element with the given test id
+///
+/// Test id is set with the [test_id][crate::test_id] function.
+///
+/// ```rust,ignore
+/// play::<MyStory>("Label is empty")
+///     .next("Check the label", |ctx| {
+///         let label = get_by_test_id(ctx.canvas(), "label")?;
+///         assert_text(&label, "")
+///     })
+/// ```
+///
+/// # Errors
+///
+/// Fails if there is no element with given test id, or if there is more than one.
+pub fn get_by_test_id(canvas: &HtmlElement, test_id: &str) -> Result<HtmlElement, StepFailure> {
+    get(canvas, By::TestId(test_id))
+}
+
+/// Returns the element with the given role
+///
+/// # Errors
+///
+/// Fails if there is no element with given role, or if there is more than one.
+pub fn get_by_role(canvas: &HtmlElement, role: &str) -> Result<HtmlElement, StepFailure> {
+    get(canvas, By::Role(role))
+}
+
+/// Returns the form control with the given label
+///
+/// # Errors
+///
+/// Fails if there is no form control with given label, or if there is more than one.
+pub fn get_by_label_text(canvas: &HtmlElement, label: &str) -> Result<HtmlElement, StepFailure> {
+    get(canvas, By::Label(label))
+}
+
+/// Returns the element showing the given text
+///
+/// # Errors
+///
+/// Fails if there is no element with given text, or if there is more than one.
+pub fn get_by_text(canvas: &HtmlElement, text: &str) -> Result<HtmlElement, StepFailure> {
+    get(canvas, By::Text(text))
+}
+
+/// Returns the markup of the canvas formatted as the Markdown code block
+///
+/// Long markup is truncated, so the failure stays readable.
+pub(crate) fn markup(canvas: &HtmlElement) -> String {
+    let html = canvas.inner_html();
+
+    let html = match html.char_indices().nth(MAX_MARKUP_LENGTH) {
+        Some((end, _)) => format!("{}…", &html[..end]),
+        None => html,
+    };
+
+    format!("Canvas:\n\n```html\n{html}\n```")
+}
@@ -0,0 +1,160 @@
+//! Tests of the pure parts of the testing helpers
+//!
+
+use super::By;
+use super::accessibility::LARGE_TEXT_CONTRAST;
+use super::accessibility::NORMAL_TEXT_CONTRAST;
+use super::accessibility::Rgba;
+use super::accessibility::Rule;
+use super::accessibility::contrast_ratio;
+use super::accessibility::is_valid_role;
+use super::accessibility::required_contrast;
+use super::events::erased;
+use super::events::key_code;
+use super::normalize_text;
+use super::queries::implicit_role;
+
+/// Whitespace is trimmed and collapsed
+#[test]
+fn normalize_text_collapses_whitespace() {
+    assert_eq!(normalize_text("  Hello \n\t world  "), "Hello world");
+    assert_eq!(normalize_text(""), "");
+}
+
+/// Tags have their implicit roles
+#[test]
+fn implicit_role_of_tags() {
+    assert_eq!(implicit_role("button", None, false), Some("button"));
+    assert_eq!(implicit_role("h3", None, false), Some("heading"));
+    assert_eq!(implicit_role("div", None, false), None);
+}
+
+/// Link requires the `href`
+#[test]
+fn implicit_role_of_links() {
+    assert_eq!(implicit_role("a", None, true), Some("link"));
+    assert_eq!(implicit_role("a", None, false), None);
+}
+
+/// Role of the input depends on its type
+#[test]
+fn implicit_role_of_inputs() {
+    assert_eq!(implicit_role("input", None, false), Some("textbox"));
+    assert_eq!(
+        implicit_role("input", Some("checkbox"), false),
+        Some("checkbox")
+    );
+    assert_eq!(
+        implicit_role("input", Some("submit"), false),
+        Some("button")
+    );
+    assert_eq!(implicit_role("input", Some("hidden"), false), None);
+}
+
+/// Queries are described in the failure messages
+#[test]
+fn by_display() {
+    assert_eq!(By::TestId("label").to_string(), "test id `label`");
+    assert_eq!(By::Role("button").to_string(), "role `button`");
+}
+
+/// Keys are mapped to the physical keys of the US keyboard
+#[test]
+fn key_codes() {
+    assert_eq!(key_code("a"), "KeyA");
+    assert_eq!(key_code("Z"), "KeyZ");
+    assert_eq!(key_code("7"), "Digit7");
+    assert_eq!(key_code(" "), "Space");
+    assert_eq!(key_code("Enter"), "Enter");
+}
+
+/// Backspace and Delete remove the character next to the cursor or the selected text
+#[test]
+fn erase_text() {
+    assert_eq!(erased("abc", 3, 3, true), Some(("ab".to_string(), 2)));
+    assert_eq!(erased("abc", 1, 1, false), Some(("ac".to_string(), 1)));
+    assert_eq!(erased("abc", 0, 0, true), None);
+    assert_eq!(erased("abc", 3, 3, false), None);
+    assert_eq!(erased("abcd", 1, 3, true), Some(("ad".to_string(), 1)));
+    assert_eq!(erased("abcd", 1, 3, false), Some(("ad".to_string(), 1)));
+    assert_eq!(erased("", 0, 0, true), None);
+}
+
+/// Characters outside of the Basic Multilingual Plane are removed whole
+#[test]
+fn erase_surrogate_pairs() {
+    assert_eq!(erased("a😀", 3, 3, true), Some(("a".to_string(), 1)));
+    assert_eq!(erased("😀b", 0, 0, false), Some(("b".to_string(), 0)));
+    assert_eq!(erased("ab", 9, 9, true), Some(("a".to_string(), 1)));
+}
+
+/// Computed colors are parsed in both the legacy and the modern syntax
+#[test]
+fn parse_colors() {
+    let black = Rgba {
+        r: 0.0,
+        g: 0.0,
+        b: 0.0,
+        a: 1.0,
+    };
+
+    assert_eq!(Rgba::parse("rgb(0, 0, 0)"), Some(black));
+    assert_eq!(
+        Rgba::parse("rgba(0, 0, 0, 0.5)"),
+        Some(Rgba { a: 0.5, ..black })
+    );
+    assert_eq!(
+        Rgba::parse("rgb(0 0 0 / 0.5)"),
+        Some(Rgba { a: 0.5, ..black })
+    );
+    assert_eq!(Rgba::parse("transparent").map(|color| color.a), Some(0.0));
+    assert_eq!(Rgba::parse("#000"), None);
+}
+
+/// Contrast ratio goes from 1:1 to 21:1
+#[test]
+fn contrast_ratios() {
+    let black = Rgba::parse("rgb(0, 0, 0)").unwrap();
+    let white = Rgba::parse("rgb(255, 255, 255)").unwrap();
+    let gray = Rgba::parse("rgb(119, 119, 119)").unwrap();
+
+    assert!((contrast_ratio(black, white) - 21.0).abs() < 0.01);
+    assert!((contrast_ratio(white, black) - 21.0).abs() < 0.01);
+    assert!((contrast_ratio(white, white) - 1.0).abs() < 0.01);
+    assert!((contrast_ratio(gray, white) - 4.48).abs() < 0.01);
+}
+
+/// Semi-transparent text is blended with its background
+#[test]
+fn blend_colors() {
+    let white = Rgba::parse("rgb(255, 255, 255)").unwrap();
+    let blended = Rgba::parse("rgba(0, 0, 0, 0.5)").unwrap().over(white);
+
+    assert!((blended.r - 127.5).abs() < 0.01);
+    assert_eq!(blended.a, 1.0);
+}
+
+/// Large and bold text requires lower contrast
+#[test]
+fn required_contrasts() {
+    assert_eq!(required_contrast(16.0, 400), NORMAL_TEXT_CONTRAST);
+    assert_eq!(required_contrast(20.0, 400), NORMAL_TEXT_CONTRAST);
+    assert_eq!(required_contrast(20.0, 700), LARGE_TEXT_CONTRAST);
+    assert_eq!(required_contrast(24.0, 400), LARGE_TEXT_CONTRAST);
+}
+
+/// Only the roles defined by the WAI-ARIA are valid
+#[test]
+fn valid_roles() {
+    assert!(is_valid_role("button"));
+    assert!(is_valid_role("tabpanel"));
+    assert!(!is_valid_role("Button"));
+    assert!(!is_valid_role("clickable"));
+}
+
+/// Rules are shown with their short names
+#[test]
+fn rule_display() {
+    assert_eq!(Rule::ImageAlt.to_string(), "image-alt");
+    assert_eq!(Rule::ColorContrast.to_string(), "color-contrast");
+}
@@ -0,0 +1,75 @@
+//! Query parameters which deep link into the story
+//!
+//! - `tab` - side panel tab to be shown, `description`, `tests` or `accessibility`
+//! - `play` - index or description of the play which is started as soon as the
+//!   story is shown. It also switches the side panel to the tests.
+//! - `speed` - speed of the started play, like `0.5x`, `fast` or `instant`
+//! - `filter` - plays shown in the tests, like `#keyboard -#slow`, see the
+//!   [PlayFilter::parse][crate::runner::PlayFilter::parse]. It also switches the
+//!   side panel to the tests.
+//!
+//! For example `/components/button?play=Click%20the%20button&speed=fast`
+
+#[cfg(test)]
+mod tests;
+
+use leptos_router::params::ParamsMap;
+
+use crate::testing::normalize_text;
+use crate::views::widgets::test_viewer::SPEEDS;
+
+/// Name of the query parameter with the side panel tab
+pub const TAB_PARAM: &str = "tab";
+/// Name of the query parameter with the play to be started
+pub const PLAY_PARAM: &str = "play";
+/// Name of the query parameter with the speed of the play
+pub const SPEED_PARAM: &str = "speed";
+/// Name of the query parameter with the filter of the plays
+pub const FILTER_PARAM: &str = "filter";
+
+/// Returns the index of the play selected by the value of the `play` parameter
+///
+/// The value is either the index of the play or its description. Descriptions
+/// are compared ignoring the case and whitespace.
+pub(crate) fn play_index(descriptions: &[&str], value: &str) -> Option<usize> {
+    if let Ok(idx) = value.trim().parse::<usize>() {
+        return (idx < descriptions.len()).then_some(idx);
+    }
+
+    let value = normalize_text(value).to_lowercase();
+
+    descriptions
+        .iter()
+        .position(|description| normalize_text(description).to_lowercase() == value)
+}
+
+/// Returns the delay between the steps in milliseconds selected by the value of the `speed` parameter
+///
+/// Accepts the labels of the speed select in the test viewer, like `0.5x`, and
+/// the names `slowest`, `slow`, `normal`, `fast` and `instant`.
+pub(crate) fn speed_delay(value: &str) -> Option<u64> {
+    let value = value.trim().to_lowercase();
+
+    let label = match value.as_str() {
+        "slowest" => "0.25x",
+        "slow" => "0.5x",
+        "normal" => "1x",
+        "fast" => "2x",
+        value => value,
+    };
+
+    SPEEDS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(label))
+        .map(|(_, delay)| *delay)
+}
+
+/// Returns the query string which starts the play when the story is opened
+///
+/// For example `?play=Counter%20shows%20the%20message`
+pub fn play_query(play: &str) -> String {
+    [(PLAY_PARAM, play.to_string())]
+        .into_iter()
+        .collect::<ParamsMap>()
+        .to_query_string()
+}
@@ -0,0 +1,25 @@
+//! Tests of the query parameters which deep link into the story
+//!
+
+use super::play_index;
+use super::speed_delay;
+
+/// Play can be selected by its index or its description
+#[test]
+fn play_by_index_or_description() {
+    let plays = ["Counter starts at zero", "Counter shows the message"];
+
+    assert_eq!(play_index(&plays, "1"), Some(1));
+    assert_eq!(play_index(&plays, "2"), None);
+    assert_eq!(play_index(&plays, "counter  shows the MESSAGE"), Some(1));
+    assert_eq!(play_index(&plays, "Unknown play"), None);
+}
+
+/// Speed can be given as the label or the name
+#[test]
+fn speed_by_label_or_name() {
+    assert_eq!(speed_delay("0.5x"), Some(1000));
+    assert_eq!(speed_delay("fast"), Some(250));
+    assert_eq!(speed_delay("Instant"), Some(0));
+    assert_eq!(speed_delay("warp"), None);
+}
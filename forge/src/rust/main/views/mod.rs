@@ -4,8 +4,10 @@ pub mod canvas;
 pub mod component_panel;
 pub mod content;
 pub mod control_pane;
+pub mod deep_link;
 pub mod description;
 pub mod section;
 pub mod story;
 pub mod tab_panel;
+pub mod test_dashboard;
 pub mod widgets;
@@ -23,6 +23,8 @@ pub enum MarkdownToken<'source> {
         len: usize,
         /// Wherever the control panel should be shown
         controls: bool,
+        /// Wherever the plays of the story should be shown under the canvas
+        tests: bool,
     },
     /// Chunk of the Markdown
     Markdown {
@@ -580,11 +582,13 @@ fn story<'source>(source: &'source str) -> Option<MarkdownToken<'source>> {
             let tag = &source["<Story".len()..end];
             let story = find_of_attribute(tag);
             let controls = parse_bool_attribute("controls", tag);
+            let tests = parse_bool_attribute("tests", tag);
 
             return Some(MarkdownToken::Story {
                 story,
                 len: end + 2,
                 controls,
+                tests,
             });
         } else {
             return None; // This is not a `<Story />` tag since it's unclosed
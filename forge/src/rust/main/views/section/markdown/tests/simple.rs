@@ -57,11 +57,13 @@ fn md_just_a_story() {
             story: Some("aa/bb"),
             len: 20,
             controls: false,
+            tests: false,
         },
         MarkdownToken::Story {
             story: Some("aa/bb"),
             len: 28,
             controls: true,
+            tests: false,
         },
     ];
 
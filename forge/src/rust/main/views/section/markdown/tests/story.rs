@@ -12,6 +12,7 @@ fn md_single_story() {
         story: Some("path"),
         len: markdown.len(),
         controls: false,
+        tests: false,
     }];
 
     let parser = MarkdownParser::new();
@@ -30,6 +31,7 @@ fn md_single_story_with_new_line() {
         story: Some("path"),
         len: markdown.len(),
         controls: false,
+        tests: false,
     }];
 
     let parser = MarkdownParser::new();
@@ -46,6 +48,7 @@ fn md_single_story_with_controls_at_the_end_of_tag_content() {
         story: Some("path"),
         len: markdown.len(),
         controls: true,
+        tests: false,
     }];
 
     let parser = MarkdownParser::new();
@@ -62,6 +65,7 @@ fn md_single_story_with_controls_at_the_beginning_of_tag_content() {
         story: Some("path"),
         len: markdown.len(),
         controls: true,
+        tests: false,
     }];
 
     let parser = MarkdownParser::new();
@@ -78,6 +82,7 @@ fn md_single_story_with_controls_surrounded_by_spaces_at_the_end() {
         story: Some("path"),
         len: markdown.len(),
         controls: true,
+        tests: false,
     }];
 
     let parser = MarkdownParser::new();
@@ -97,6 +102,7 @@ controls
         story: Some("path"),
         len: markdown.len(),
         controls: true,
+        tests: false,
     }];
 
     let parser = MarkdownParser::new();
@@ -117,6 +123,41 @@ controlscontrols
         story: Some("path"),
         len: markdown.len(),
         controls: false,
+        tests: false,
+    }];
+
+    let parser = MarkdownParser::new();
+    let result = parser.parse(markdown);
+
+    assert_eq!(result, expected);
+}
+
+/// Story with tests and controls
+#[test]
+fn md_single_story_with_tests() {
+    let markdown = r####"<Story of="path" controls tests />"####;
+    let expected = vec![MarkdownToken::Story {
+        story: Some("path"),
+        len: markdown.len(),
+        controls: true,
+        tests: true,
+    }];
+
+    let parser = MarkdownParser::new();
+    let result = parser.parse(markdown);
+
+    assert_eq!(result, expected);
+}
+
+/// Story where tests are not present because `tests` is only a part of the path
+#[test]
+fn md_single_story_without_tests_in_path() {
+    let markdown = r####"<Story of="guides/adding_tests" />"####;
+    let expected = vec![MarkdownToken::Story {
+        story: Some("guides/adding_tests"),
+        len: markdown.len(),
+        controls: false,
+        tests: false,
     }];
 
     let parser = MarkdownParser::new();
@@ -140,6 +181,7 @@ mod internal {
             story: Some("path"),
             len: markdown.len(),
             controls: false,
+            tests: false,
         });
 
         let result = story(markdown);
@@ -2,10 +2,14 @@
 
 #![allow(clippy::missing_docs_in_private_items)]
 
+use std::any::type_name;
 use std::marker::PhantomData;
 
 use leptos::html::Div;
 use leptos::prelude::*;
+use leptos_router::NavigateOptions;
+use leptos_router::hooks::use_location;
+use leptos_router::hooks::use_navigate;
 use ui_components::widgets::details::Details;
 use utils::prelude::ThreadSafe;
 use utils_leptos::signal::URwSignal;
@@ -17,10 +21,21 @@ use super::description::Description;
 use super::tab_panel::TabName;
 use crate::IntoStory;
 use crate::Story;
+use crate::runner::PlayFilter;
 use crate::views::canvas::EmbeddedCanvas;
 use crate::views::control_pane::EmbeddedControlPane;
+use crate::views::deep_link::FILTER_PARAM;
+use crate::views::deep_link::PLAY_PARAM;
+use crate::views::deep_link::SPEED_PARAM;
+use crate::views::deep_link::TAB_PARAM;
+use crate::views::deep_link::play_index;
+use crate::views::deep_link::speed_delay;
 use crate::views::tab_panel::Tab;
 use crate::views::tab_panel::TabPanel;
+use crate::views::widgets::accessibility_audit::AccessibilityAudit;
+use crate::views::widgets::play_recorder::PlayRecorder;
+use crate::views::widgets::test_viewer::StoryLifecycle;
+use crate::views::widgets::test_viewer::StoryLifecycleControls;
 use crate::views::widgets::test_viewer::TestView;
 
 /// Page is the view for the single story about a component
@@ -30,32 +45,105 @@ pub fn Story<S: 'static + IntoStory + Default + Copy + ThreadSafe>(
     #[prop(optional)]
     _story: PhantomData<S>,
 ) -> impl IntoView {
-    let story = S::default().into_story();
-    let canvas = NodeRef::new();
-
-    let tabs: Vec<Box<dyn Tab<SidePanelTabs> + 'static>> = vec![
-        Box::new(DescriptionTab {
-            text: story.description(),
-        }),
-        Box::new(TestsTabs { story, canvas }),
-    ];
-
-    let selector = URwSignal::new(SidePanelTabs::Description);
-
-    view! {
-        <>
-            <ComponentPanel>
-                <Canvas story=story node_ref=canvas />
-                <TabPanel
-                    id="side-panel"
-                    tabs
-                    selector
-                />
-            </ComponentPanel>
-            <div class="flex flex-col basis-1/3 first:basis-1/1 px-4 py-4 overflow-auto print:hidden print:basis-0 min-w-xs w-xs shrink-0 @md:shrink-1">
-                <ControlPane story=story />
-            </div>
-        </>
+    let lifecycle = StoryLifecycle::new();
+    let location = use_location();
+    let navigate = use_navigate();
+
+    // Results of the plays are remembered under the path of the story
+    lifecycle.set_path(location.pathname.get_untracked());
+
+    // Tab and play can be selected in the URL, see the [deep_link][crate::views::deep_link]
+    let query = location.query.get_untracked();
+    let mut tab = query
+        .get_str(TAB_PARAM)
+        .and_then(SidePanelTabs::from_query)
+        .unwrap_or(SidePanelTabs::Description);
+
+    if let Some(play) = query.get_str(PLAY_PARAM) {
+        let story = S::default().into_story();
+        let plays = story.plays();
+        let descriptions = plays
+            .iter()
+            .map(|play| play.description())
+            .collect::<Vec<_>>();
+
+        if let Some(play) = play_index(&descriptions, play) {
+            if let Some(delay) = query.get_str(SPEED_PARAM).and_then(speed_delay) {
+                lifecycle.set_delay(delay);
+            }
+            lifecycle.play_on_mount(play);
+            tab = SidePanelTabs::Tests;
+        }
+    }
+
+    if let Some(filter) = query.get_str(FILTER_PARAM) {
+        lifecycle.filter().set(filter.to_string());
+        tab = SidePanelTabs::Tests;
+    }
+
+    let selector = URwSignal::new(tab);
+
+    // Selected tab is reflected in the URL, so the link to the story opens the same tab
+    Effect::new(move || {
+        let tab = selector.get();
+        let mut query = location.query.get_untracked();
+
+        let in_url = query.get_str(TAB_PARAM).and_then(SidePanelTabs::from_query);
+        if in_url.unwrap_or(SidePanelTabs::Description) != tab {
+            query.replace(TAB_PARAM, tab.query_value().to_string());
+            let url = format!(
+                "{}{}",
+                location.pathname.get_untracked(),
+                query.to_query_string()
+            );
+
+            navigate(
+                &url,
+                NavigateOptions {
+                    replace: true,
+                    scroll: false,
+                    ..Default::default()
+                },
+            );
+        }
+    });
+
+    // Every reset creates a new instance of the story, so the canvas, controls
+    // and tests are rebuilt from scratch
+    move || {
+        lifecycle.track();
+        lifecycle.measure_render();
+
+        let story = S::default().into_story();
+        let canvas = NodeRef::new();
+
+        let tabs: Vec<Box<dyn Tab<SidePanelTabs> + 'static>> = vec![
+            Box::new(DescriptionTab {
+                text: story.description(),
+            }),
+            Box::new(TestsTabs {
+                story,
+                canvas,
+                lifecycle,
+            }),
+            Box::new(AccessibilityTab { canvas }),
+        ];
+
+        view! {
+            <>
+                <ComponentPanel>
+                    <Canvas story=story node_ref=canvas />
+                    <TabPanel
+                        id="side-panel"
+                        tabs
+                        selector
+                    />
+                </ComponentPanel>
+                <div class="flex flex-col basis-1/3 first:basis-1/1 px-4 py-4 overflow-auto print:hidden print:basis-0 min-w-xs w-xs shrink-0 @md:shrink-1">
+                    <ControlPane story=story />
+                </div>
+            </>
+        }
     }
 }
 
@@ -63,6 +151,7 @@ pub fn Story<S: 'static + IntoStory + Default + Copy + ThreadSafe>(
 enum SidePanelTabs {
     Description,
     Tests,
+    Accessibility,
 }
 
 impl TabName for SidePanelTabs {
@@ -71,6 +160,7 @@ impl TabName for SidePanelTabs {
         match self {
             Description => "Description",
             Tests => "Tests",
+            Accessibility => "Accessibility",
         }
     }
 
@@ -79,6 +169,7 @@ impl TabName for SidePanelTabs {
         match self {
             Description => "description-tab",
             Tests => "tests-tab",
+            Accessibility => "accessibility-tab",
         }
     }
 
@@ -87,11 +178,32 @@ impl TabName for SidePanelTabs {
         match name {
             "Description" => Some(Description),
             "Tests" => Some(Tests),
+            "Accessibility" => Some(Accessibility),
             _ => None,
         }
     }
 }
 
+impl SidePanelTabs {
+    /// Value of the `tab` query parameter selecting this tab
+    fn query_value(&self) -> &'static str {
+        use SidePanelTabs::*;
+        match self {
+            Description => "description",
+            Tests => "tests",
+            Accessibility => "accessibility",
+        }
+    }
+
+    /// Returns the tab selected by the value of the `tab` query parameter
+    fn from_query(value: &str) -> Option<Self> {
+        use SidePanelTabs::*;
+        [Description, Tests, Accessibility]
+            .into_iter()
+            .find(|tab| tab.query_value().eq_ignore_ascii_case(value.trim()))
+    }
+}
+
 struct DescriptionTab {
     text: &'static str,
 }
@@ -112,51 +224,136 @@ impl Tab<SidePanelTabs> for DescriptionTab {
 struct TestsTabs<StoryImpl: Story> {
     story: StoryImpl,
     canvas: NodeRef<Div>,
+    lifecycle: StoryLifecycle,
 }
 
 impl<StoryImpl> Tab<SidePanelTabs> for TestsTabs<StoryImpl>
 where
-    StoryImpl: Story + ThreadSafe,
+    StoryImpl: 'static + Story + ThreadSafe,
 {
     fn id(&self) -> SidePanelTabs {
         SidePanelTabs::Tests
     }
 
     fn view(&self) -> AnyView {
-        let plays = self.story.plays();
         let canvas = self.canvas;
         let story = self.story;
+        let lifecycle = self.lifecycle;
 
-        if !plays.is_empty() {
-            let tests = plays
-                .iter()
-                .enumerate()
-                .map(|(idx, _play)| TestView::new(story, idx, canvas))
-                .collect::<Vec<_>>();
-
-            let views = tests
-                .iter()
-                .map(|test| {
-                    view! {
-                        <Details details={test} />
-                    }
-                })
-                .collect_view();
+        (view! {
+            <div class="flex-row basis-1/3 first:basis-1/1 px-4 py-4 overflow-auto">
+                <StoryPlays story canvas lifecycle />
+                <PlayRecorder story=type_name::<StoryImpl>() canvas />
+            </div>
+        })
+        .into_any()
+    }
+}
 
-            (view! {
-                <div class="flex-row basis-1/3 first:basis-1/1 px-4 py-4 overflow-auto">
-                    {views}
-                </div>
+struct AccessibilityTab {
+    canvas: NodeRef<Div>,
+}
+
+impl Tab<SidePanelTabs> for AccessibilityTab {
+    fn id(&self) -> SidePanelTabs {
+        SidePanelTabs::Accessibility
+    }
+
+    fn view(&self) -> AnyView {
+        let canvas = self.canvas;
+
+        (view! {
+            <div class="flex-row basis-1/3 first:basis-1/1 px-4 py-4 overflow-auto">
+                <AccessibilityAudit canvas />
+            </div>
+        })
+        .into_any()
+    }
+}
+
+/// List of the plays of the story which can be run on the canvas
+#[component]
+fn StoryPlays<StoryImpl: 'static + Story + ThreadSafe>(
+    /// Story which plays are shown
+    story: StoryImpl,
+    /// Canvas on which the plays are run
+    canvas: NodeRef<Div>,
+    /// Lifetime of the story instance
+    lifecycle: StoryLifecycle,
+) -> impl IntoView {
+    let plays = story.plays();
+
+    if !plays.is_empty() {
+        let filter = lifecycle.filter();
+        let total = plays.len();
+
+        let tests = plays
+            .iter()
+            .enumerate()
+            .map(|(idx, _play)| TestView::new(story, idx, canvas, lifecycle))
+            .collect::<Vec<_>>();
+
+        // Plays which don't match the filter are only hidden, so they keep their results
+        let matching = plays
+            .iter()
+            .map(|play| {
+                let description = play.description();
+                let tags = play.tags();
+                move || filter.with(|query| PlayFilter::parse(query).matches(description, &tags))
             })
-            .into_any()
-        } else {
-            (view! {
-                <div class="flex-row basis-1/3 first:basis-1/1 px-4 py-4 overflow-auto">
-                    <div class="leptos-forge-message-box">No tests were defined!</div>
-                </div>
+            .collect::<Vec<_>>();
+
+        let shown = {
+            let matching = matching.clone();
+            move || {
+                let shown = matching.iter().filter(|matches| matches()).count();
+                (shown < total).then(|| format!("{shown} of {total} plays"))
+            }
+        };
+
+        let views = tests
+            .iter()
+            .zip(matching)
+            .map(|(test, matches)| {
+                let class = move || {
+                    if matches() {
+                        "leptos-forge-play"
+                    } else {
+                        "leptos-forge-play hidden"
+                    }
+                };
+
+                view! {
+                    <div class=class>
+                        <Details details={test} />
+                    </div>
+                }
             })
-            .into_any()
-        }
+            .collect_view();
+
+        (view! {
+            <StoryLifecycleControls lifecycle />
+            <div class="leptos-forge-play-filter flex flex-row items-center pb-2">
+                <label class="grow-1 px-2 py-2">
+                    "Filter"
+                    <input
+                        type="text"
+                        class="ml-2"
+                        placeholder="#keyboard -#slow text"
+                        prop:value=move || filter.get()
+                        on:input=move |ev| filter.set(event_target_value(&ev))
+                    />
+                </label>
+                <div class="flex-none px-2 py-2">{shown}</div>
+            </div>
+            {views}
+        })
+        .into_any()
+    } else {
+        (view! {
+            <div class="leptos-forge-message-box">No tests were defined!</div>
+        })
+        .into_any()
     }
 }
 
@@ -172,38 +369,62 @@ pub fn EmbeddedStory<S: 'static + IntoStory + Default + Copy + ThreadSafe>(
     #[prop(default = false)]
     #[allow(unused_variables)]
     description: bool,
-    /// If set to `true` embedded story will allow you tu run the tests on itself
+    /// If set to `true` embedded story will allow you to run the plays on itself
     ///
-    /// Currently we don't support this part
+    /// Plays are shown under the canvas, so it's ignored if the `view` is `false`
     #[prop(default = false)]
-    _tests: bool,
+    tests: bool,
     /// Phantom data of the story
     #[prop(optional)]
     _story: PhantomData<S>,
 ) -> impl IntoView {
-    let canvas_ref = NodeRef::new();
-    let story = S::default().into_story();
-    let canvas = if view {
-        // let v = story.view().into_any();
-        Some(view! {
-            <EmbeddedCanvas story node_ref=canvas_ref />
-        })
-    } else {
-        None
-    };
+    let lifecycle = StoryLifecycle::new();
 
-    let control_pane = if controls {
-        Some(view! {
-            <EmbeddedControlPane story />
-        })
-    } else {
-        None
-    };
-
-    view! {
-        <div class="flex flex-col">
-            {canvas}
-            {control_pane}
-        </div>
+    // The same story can be embedded in many pages and every page can embed many
+    // stories, so the results of the plays are remembered under both of them
+    let page = use_location().pathname.get_untracked();
+    lifecycle.set_path(format!("{page}@{}", type_name::<S>()));
+
+    // Same as in the [Story], every reset rebuilds the embedded story from scratch
+    move || {
+        lifecycle.track();
+        lifecycle.measure_render();
+
+        let canvas_ref = NodeRef::new();
+        let story = S::default().into_story();
+        let canvas = if view {
+            Some(view! {
+                <EmbeddedCanvas story node_ref=canvas_ref />
+            })
+        } else {
+            None
+        };
+
+        let control_pane = if controls {
+            Some(view! {
+                <EmbeddedControlPane story />
+            })
+        } else {
+            None
+        };
+
+        let plays = if view && tests {
+            Some(view! {
+                <div class="leptos-forge-embedded-plays px-4 py-4">
+                    <StoryPlays story canvas=canvas_ref lifecycle />
+                </div>
+            })
+        } else {
+            None
+        };
+
+        // Outlines of the steps are positioned relative to the parent of the canvas
+        view! {
+            <div class="flex flex-col relative">
+                {canvas}
+                {control_pane}
+                {plays}
+            </div>
+        }
     }
 }
@@ -0,0 +1,326 @@
+//! Dashboard which runs every play of every story
+//!
+
+use leptos::html::Div;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos::web_sys::HtmlElement;
+use leptos_router::components::A;
+use reactive_stores::Store;
+use ui_components::menu::MenuState;
+use ui_components::menu::Navigate;
+use ui_components::primitives::markdown::Markdown;
+
+use crate::RouteDef;
+use crate::runner::DescriptionStatus;
+use crate::runner::PlayFilter;
+use crate::runner::RunOptions;
+use crate::runner::Status;
+use crate::runner::StoryCoverage;
+use crate::runner::StoryReport;
+use crate::runner::coverage;
+use crate::runner::format_duration;
+use crate::runner::stories;
+use crate::views::deep_link::play_query;
+
+//
+// If you make changes in the dashboard, please remember to update also
+//
+// - **site `documentation/stories/testing`** - it describes how to run all of the plays
+//
+
+/// First segment of the path of the test dashboard
+pub const TEST_DASHBOARD_SEGMENT_1: &str = "leptos_forge";
+/// Second segment of the path of the test dashboard
+pub const TEST_DASHBOARD_SEGMENT_2: &str = "tests";
+/// Path of the test dashboard
+pub const TEST_DASHBOARD_PATH: &str = "/leptos_forge/tests";
+
+/// State of the test run started from the dashboard
+///
+/// It's created by the [App][crate::App] so the results survive navigating
+/// away from the dashboard.
+#[derive(Debug, Clone, Copy)]
+pub struct TestRunState {
+    /// Reports of the stories which were already run
+    reports: RwSignal<Vec<StoryReport>>,
+    /// Label of the story which is running right now
+    current: RwSignal<Option<String>>,
+    /// Number of failed plays after the last completed run
+    ///
+    /// It's `None` if the tests were never run
+    failed: RwSignal<Option<usize>>,
+    /// Query selecting the plays which are run, see [PlayFilter::parse]
+    filter: RwSignal<String>,
+}
+
+impl TestRunState {
+    /// Create new instance of the TestRunState
+    pub fn new() -> Self {
+        Self {
+            reports: RwSignal::new(Vec::new()),
+            current: RwSignal::new(None),
+            failed: RwSignal::new(None),
+            filter: RwSignal::new(String::new()),
+        }
+    }
+
+    /// Returns `true` if the tests are running right now
+    fn is_running(&self) -> bool {
+        self.current.get().is_some()
+    }
+
+    /// Number of plays with a given status in the reports collected so far
+    fn count(&self, status: Status) -> usize {
+        self.reports
+            .with(|reports| reports.iter().map(|story| story.count(status)).sum())
+    }
+}
+
+impl Default for TestRunState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs the plays matching the filter, skips the other ones and updates the `state`
+async fn run_all(routes: Vec<RouteDef>, state: TestRunState, canvas: Option<HtmlElement>) {
+    let options = RunOptions {
+        container: canvas,
+        filter: PlayFilter::parse(&state.filter.get_untracked()),
+    };
+
+    state.reports.set(Vec::new());
+
+    for story in stories(&routes) {
+        state.current.set(Some(story.label().to_string()));
+        let report = story.run(&options).await;
+        state.reports.update(|reports| reports.push(report));
+    }
+
+    state.failed.set(Some(state.count(Status::Failed)));
+    state.current.set(None);
+}
+
+/// Dashboard which runs every play of every story and shows the results
+#[component]
+pub fn TestDashboard(
+    /// Routes of the application
+    routes: Vec<RouteDef>,
+    /// State of the test run
+    state: TestRunState,
+) -> impl IntoView {
+    let canvas: NodeRef<Div> = NodeRef::new();
+
+    let coverage = coverage(&routes);
+    let coverage_summary = coverage.summary();
+    let coverage_rows = coverage
+        .stories
+        .into_iter()
+        .map(|story| view! { <StoryCoverageRow story /> })
+        .collect_view();
+
+    let run = move |_| {
+        if state.current.get_untracked().is_none() {
+            let routes = routes.clone();
+            let canvas: Option<HtmlElement> = canvas.get_untracked().map(|canvas| canvas.into());
+
+            spawn_local(run_all(routes, state, canvas));
+        }
+    };
+
+    let summary = move || {
+        format!(
+            "{} passed, {} failed, {} skipped",
+            state.count(Status::Passed),
+            state.count(Status::Failed),
+            state.count(Status::Skipped),
+        )
+    };
+
+    let current = move || state.current.get().map(|label| format!("Running: {label}"));
+
+    let stories = move || {
+        state
+            .reports
+            .get()
+            .into_iter()
+            .map(|story| view! { <StoryResult story /> })
+            .collect_view()
+    };
+
+    view! {
+        <div class="leptos-forge-test-dashboard scrollbox w-full flex flex-col">
+            <div class="scrollable px-4 py-4">
+                <div class="flex flex-row bg-forgegray-100 items-center">
+                    <div class="flex-none text-base font-bold px-2 py-2">Run all tests</div>
+                    <div class="flex-none px-2 py-2">{summary}</div>
+                    <div class="flex-none px-2 py-2">{current}</div>
+                    <div class="grow-1" inner_html="&nbsp;"/>
+                    <label class="flex-none px-2 py-2">
+                        "Filter"
+                        <input
+                            type="text"
+                            class="ml-2"
+                            placeholder="#smoke -#slow text"
+                            prop:value=move || state.filter.get()
+                            on:input=move |ev| state.filter.set(event_target_value(&ev))
+                        />
+                    </label>
+                    <button
+                        class="leptos-forge-test-view-button bg-forgegray-300 hover:bg-forgeblue-400 active:bg-forgeblue-600 active:text-forgegray-200 px-2 py-2"
+                        disabled=move || state.is_running()
+                        on:click=run
+                    >Run</button>
+                </div>
+                <table class="leptos-forge-test-dashboard-results w-full text-left">
+                    <thead>
+                        <tr>
+                            <th class="px-2 py-1">Story</th>
+                            <th class="px-2 py-1">Passed</th>
+                            <th class="px-2 py-1">Failed</th>
+                            <th class="px-2 py-1">Skipped</th>
+                            <th class="px-2 py-1">Time</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {stories}
+                    </tbody>
+                </table>
+                <div class="flex flex-row bg-forgegray-100 items-center mt-4">
+                    <div class="flex-none text-base font-bold px-2 py-2">Coverage</div>
+                    <div class="flex-none px-2 py-2">{coverage_summary}</div>
+                </div>
+                <table class="leptos-forge-test-dashboard-coverage w-full text-left">
+                    <thead>
+                        <tr>
+                            <th class="px-2 py-1">Story</th>
+                            <th class="px-2 py-1">Plays</th>
+                            <th class="px-2 py-1">Steps</th>
+                            <th class="px-2 py-1">Description</th>
+                            <th class="px-2 py-1">Issues</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {coverage_rows}
+                    </tbody>
+                </table>
+                <div class="leptos-forge-canvas m-4 bg-forgegray-100" node_ref=canvas />
+            </div>
+        </div>
+    }
+}
+
+/// Results of the single story in the dashboard
+#[component]
+fn StoryResult(
+    /// Report of the story
+    story: StoryReport,
+) -> impl IntoView {
+    let status_class = match story.status() {
+        Status::Failed => "text-red-700",
+        Status::Passed | Status::Skipped => "",
+    };
+
+    let failures = story
+        .plays
+        .iter()
+        .filter(|play| play.status == Status::Failed)
+        .map(|play| {
+            let step = play
+                .failed_step()
+                .map(|step| format!(" - step: {}", step.step))
+                .unwrap_or_default();
+            let message = play
+                .failure()
+                .map(|failure| failure.to_markdown())
+                .unwrap_or_default();
+            // Link starts the failed play, so it can be watched step by step
+            let href = format!("{}{}", story.path, play_query(&play.play));
+            let play = play.play.clone();
+
+            view! {
+                <li>
+                    <A href>{play}</A>{step}
+                    <div class="markdown pl-4">
+                        <Markdown src=message />
+                    </div>
+                </li>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <tr class=status_class>
+            <td class="px-2 py-1"><A href=story.path.clone()>{story.label.clone()}</A></td>
+            <td class="px-2 py-1">{story.count(Status::Passed)}</td>
+            <td class="px-2 py-1">{story.count(Status::Failed)}</td>
+            <td class="px-2 py-1">{story.count(Status::Skipped)}</td>
+            <td class="px-2 py-1">{format_duration(story.duration_ms())}</td>
+        </tr>
+        <tr>
+            <td colspan="5">
+                <ul class="list-none pl-4">{failures}</ul>
+            </td>
+        </tr>
+    }
+}
+
+/// Coverage of the single story in the dashboard
+#[component]
+fn StoryCoverageRow(
+    /// Coverage of the story
+    story: StoryCoverage,
+) -> impl IntoView {
+    let class = if story.is_covered() {
+        ""
+    } else {
+        "text-red-700"
+    };
+    let description = match story.description {
+        DescriptionStatus::Custom => "custom",
+        DescriptionStatus::Default => "default",
+        DescriptionStatus::Missing => "missing",
+    };
+    let issues = story.issues().join(", ");
+
+    view! {
+        <tr class=class>
+            <td class="px-2 py-1"><A href=story.path.clone()>{story.label.clone()}</A></td>
+            <td class="px-2 py-1">{story.plays}</td>
+            <td class="px-2 py-1">{story.steps}</td>
+            <td class="px-2 py-1">{description}</td>
+            <td class="px-2 py-1">{issues}</td>
+        </tr>
+    }
+}
+
+/// Menu entry of the test dashboard with a badge showing the number of failed plays
+#[component]
+pub fn TestDashboardMenuItem<'a>(
+    /// Current location of the browser window
+    location: &'a str,
+    /// State of the menu
+    store: Store<MenuState>,
+    /// State of the test run
+    state: TestRunState,
+) -> impl IntoView {
+    let badge = move || {
+        state.failed.get().map(|failed| {
+            let class = if failed > 0 {
+                "ml-2 px-2 rounded-full text-sm bg-red-700 text-forgegray-50"
+            } else {
+                "ml-2 px-2 rounded-full text-sm bg-green-700 text-forgegray-50"
+            };
+
+            view! { <span class=class>{failed}</span> }
+        })
+    };
+
+    view! {
+        <div class="flex flex-row items-center">
+            <Navigate to=TEST_DASHBOARD_PATH label="Run all tests" class="ml-6 border-l-0!" location store />
+            {badge}
+        </div>
+    }
+}
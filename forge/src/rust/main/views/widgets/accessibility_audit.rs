@@ -0,0 +1,99 @@
+//! Widget running the accessibility audit of the canvas
+//!
+//! The audit is run on demand, because the story can be in the middle of a play
+//! and the canvas changes all the time. Every finding links to the offending
+//! element, clicking it scrolls the element into view and outlines it for a moment.
+
+use leptos::html::Div;
+use leptos::prelude::*;
+use leptos::web_sys::HtmlElement;
+use ui_components::primitives::markdown::Markdown;
+
+use crate::runner::highlight;
+use crate::testing::Violation;
+use crate::testing::audit;
+use crate::testing::describe;
+use crate::views::widgets::test_viewer::test_view_button_class;
+
+/// Scrolls the `element` into view and outlines it for a moment, labeled with the `rule`
+fn show_element(canvas: &HtmlElement, element: &HtmlElement, rule: &str) {
+    element.scroll_into_view();
+    highlight(canvas, &[element.clone().into()], rule, false);
+}
+
+/// Runs the accessibility audit of the canvas and shows the findings
+#[component]
+pub fn AccessibilityAudit(
+    /// Canvas to be audited
+    canvas: NodeRef<Div>,
+) -> impl IntoView {
+    // Violations hold the DOM elements, which can't be sent between threads
+    let violations = RwSignal::new_local(None::<Vec<Violation>>);
+
+    let run_audit = move |_| {
+        if let Some(canvas) = canvas.get_untracked() {
+            violations.set(Some(audit(&canvas)));
+        }
+    };
+
+    let findings = move || {
+        violations.with(|violations| match violations {
+            None => (view! {
+                <div class="leptos-forge-message-box">
+                    Run the audit to check the canvas for the common accessibility problems.
+                </div>
+            })
+            .into_any(),
+            Some(violations) if violations.is_empty() => (view! {
+                <div class="leptos-forge-message-box">No accessibility problems were found!</div>
+            })
+            .into_any(),
+            Some(violations) => {
+                let items = violations
+                    .iter()
+                    .map(|violation| {
+                        let element = violation.element.clone();
+                        let rule = violation.rule.to_string();
+                        let label = rule.clone();
+                        let target = describe(&violation.element);
+                        let message = violation.message.clone();
+
+                        view! {
+                            <li class="py-1">
+                                <span class="font-bold pr-2">{rule}</span>
+                                <a
+                                    class="leptos-forge-accessibility-target cursor-pointer underline font-mono"
+                                    title="Show the element in the canvas"
+                                    on:click=move |_| {
+                                        if let Some(canvas) = canvas.get_untracked() {
+                                            show_element(&canvas, &element, &label);
+                                        }
+                                    }
+                                >{target}</a>
+                                <div class="markdown pl-4">
+                                    <Markdown src={message} />
+                                </div>
+                            </li>
+                        }
+                    })
+                    .collect_view();
+
+                (view! {
+                    <ul class="leptos-forge-accessibility-findings list-none">{items}</ul>
+                })
+                .into_any()
+            }
+        })
+    };
+
+    view! {
+        <div class="leptos-forge-accessibility-audit flex flex-row items-center pb-2">
+            <div class="grow-1" inner_html="&nbsp;"/>
+            <button
+                on:click=run_audit
+                {..test_view_button_class("flex-none")}
+            >Audit</button>
+        </div>
+        {findings}
+    }
+}
@@ -1,3 +1,5 @@
 //! Custom more complex widgets specific to the `leptos_forge`
 
+pub mod accessibility_audit;
+pub mod play_recorder;
 pub mod test_viewer;
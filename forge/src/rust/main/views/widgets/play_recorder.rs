@@ -0,0 +1,114 @@
+//! Widget recording the interactions with the canvas as the source of a play
+//!
+//! While recording, every click, typed text, pressed key and selected option is
+//! added to the generated `play::<S>("...").next(...)` chain, which can be copied
+//! into the `plays` method of the story.
+
+use leptos::html::Div;
+use leptos::prelude::*;
+use ui_components::primitives::markdown::Markdown;
+
+use crate::runner::RecordedAction;
+use crate::runner::Recorder;
+use crate::runner::play_code;
+use crate::runner::push_action;
+use crate::views::widgets::test_viewer::test_view_button_class;
+
+/// Description of the generated play, until the user changes it
+const DEFAULT_DESCRIPTION: &str = "Recorded play";
+
+/// Records the interactions with the canvas and shows the generated play
+#[component]
+pub fn PlayRecorder(
+    /// Full name of the type of the story, as returned by the [std::any::type_name]
+    story: &'static str,
+    /// Canvas on which the interactions are recorded
+    canvas: NodeRef<Div>,
+) -> impl IntoView {
+    let actions = RwSignal::new(Vec::<RecordedAction>::new());
+    let description = RwSignal::new(DEFAULT_DESCRIPTION.to_string());
+    // Recorder holds the DOM listeners, which can't be sent between threads
+    let recorder = StoredValue::new_local(None::<Recorder>);
+    let recording = RwSignal::new(false);
+
+    let start = move |_| {
+        if let Some(canvas) = canvas.get_untracked() {
+            let on_action = move |action| actions.update(|actions| push_action(actions, action));
+            recorder.set_value(Some(Recorder::start(&canvas, on_action)));
+            recording.set(true);
+        }
+    };
+    let stop = move |_| {
+        recorder.set_value(None);
+        recording.set(false);
+    };
+    let clear = move |_| actions.set(Vec::new());
+
+    // Listeners must not outlive the canvas, which is rebuilt on every reset
+    on_cleanup(move || {
+        recorder.try_set_value(None);
+    });
+
+    let controls = move || {
+        if recording.get() {
+            (view! {
+                <button on:click=stop {..test_view_button_class("flex-none")}>Stop</button>
+            })
+            .into_any()
+        } else {
+            (view! {
+                <button on:click=start {..test_view_button_class("flex-none")}>Record</button>
+            })
+            .into_any()
+        }
+    };
+
+    let code = move || {
+        actions.with(|actions| {
+            if actions.is_empty() {
+                let message = if recording.get() {
+                    "Interact with the canvas, every action becomes a step of the play."
+                } else {
+                    "Record the interactions with the canvas to generate the code of a play."
+                };
+
+                (view! {
+                    <div class="leptos-forge-message-box">{message}</div>
+                })
+                .into_any()
+            } else {
+                let code = description.with(|description| play_code(story, description, actions));
+
+                (view! {
+                    <div class="leptos-forge-recorded-play markdown">
+                        <Markdown src={format!("```rust\n{code}\n```")} />
+                    </div>
+                })
+                .into_any()
+            }
+        })
+    };
+
+    view! {
+        <div class="leptos-forge-play-recorder pt-4">
+            <div class="flex flex-row items-center pb-2">
+                <label class="grow-1 px-2 py-2">
+                    "Play"
+                    <input
+                        type="text"
+                        class="ml-2"
+                        prop:value=move || description.get()
+                        on:input=move |ev| description.set(event_target_value(&ev))
+                    />
+                </label>
+                {controls}
+                <button
+                    on:click=clear
+                    disabled=move || actions.with(Vec::is_empty)
+                    {..test_view_button_class("flex-none ml-2")}
+                >Clear</button>
+            </div>
+            {code}
+        </div>
+    }
+}
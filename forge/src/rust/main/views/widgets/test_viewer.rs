@@ -1,12 +1,15 @@
 //! Widget which shows the tests for the story
 //!
 
+use std::collections::HashSet;
 use std::fmt::Display;
 use std::time::Duration;
 
 use leptos::attr::Attribute;
 use leptos::html::Div;
 use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos::web_sys::HtmlElement;
 use reactive_stores::Field;
 use reactive_stores::KeyMap;
 use reactive_stores::Patch;
@@ -14,11 +17,32 @@ use reactive_stores::PatchField;
 use reactive_stores::Store;
 use reactive_stores::StoreFieldIterator;
 use reactive_stores::StorePath;
+use ui_components::primitives::markdown::Markdown;
 use ui_components::widgets::details::DetailsParts;
 
+use crate::POLL_INTERVAL;
 use crate::Story;
+use crate::runner::ConsoleCapture;
+use crate::runner::DomDiff;
+use crate::runner::MutationRecorder;
+use crate::runner::PlayHistory;
+use crate::runner::ScheduledStep;
+use crate::runner::StepLogs;
+use crate::runner::clear_highlight;
+use crate::runner::execute_step;
+use crate::runner::format_duration;
+use crate::runner::highlight;
+use crate::runner::on_panic;
+use crate::runner::schedule;
+use crate::runner::take_targets;
 use crate::story::Play;
+use crate::story::Scratch;
 use crate::story::Step;
+use crate::story::StepContext;
+use crate::story::StepFailure;
+use crate::story::StepResult;
+use crate::story::now;
+use crate::story::sleep;
 
 //
 // If you make changes in the components of the test viewer and runner, please remember to update also
@@ -30,13 +54,16 @@ use crate::story::Step;
 
 /// Controls how the execution of the test will be handled
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
-#[allow(dead_code)]
 enum ExecutionMode {
     /// Run a single step at a time
     Step,
     /// Play whole test
     Play,
+    /// Play was paused, by the user or a breakpoint, and it waits to be resumed
+    Paused,
     /// Do not run the test
+    ///
+    /// Setting this mode while the test is played stops it after the current step
     #[default]
     Stopped,
 }
@@ -57,14 +84,16 @@ impl PatchField for ExecutionMode {
 }
 
 /// Result of the test execution
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 enum TestResult {
     /// Success of the test
     Success,
     /// Failure of the test
-    Failure(&'static str),
+    Failure(StepFailure),
     /// In progress of the test
     InProgress,
+    /// Test was stopped by the user before all of the steps were run
+    Stopped,
     /// Didn't run yet
     #[default]
     NotRun,
@@ -80,7 +109,7 @@ impl TestResult {
     fn is_complete(&self) -> bool {
         use TestResult::*;
         match self {
-            Success | Failure(_) => true,
+            Success | Failure(_) | Stopped => true,
             InProgress | NotRun => false,
         }
     }
@@ -106,19 +135,16 @@ impl Display for TestResult {
         use TestResult::*;
         match self {
             Success => f.write_str("[SUCCESS]"),
-            Failure(reason) => {
-                f.write_str("[FAILURE: ")?;
-                f.write_str(reason)?;
-                f.write_str("]")
-            }
+            Failure(_) => f.write_str("[FAILURE]"),
             InProgress => f.write_str("[IN PROGRESS]"),
+            Stopped => f.write_str("[STOPPED]"),
             NotRun => f.write_str("[NOT RUN]"),
         }
     }
 }
 
 /// State of the test execution
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Store, Patch)]
+#[derive(Debug, Clone, PartialEq, Eq, Store, Patch)]
 struct TestState {
     /// id of the test
     ///
@@ -126,6 +152,81 @@ struct TestState {
     test_id: usize,
     /// Result of the last test execution
     result: TestResult,
+    /// Changes which the last execution of the step made in the canvas
+    changes: DomDiff,
+    /// Console output and panic of the last execution of the step
+    logs: StepLogs,
+    /// Duration and budget of the last execution of the step
+    timing: StepTiming,
+}
+
+/// How long the last execution of the step took
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct StepTiming {
+    /// Duration of the step, `None` if the step wasn't run yet
+    duration: Option<Duration>,
+    /// Performance budget of the step, see [Step::budget]
+    budget: Option<Duration>,
+}
+
+impl Display for StepTiming {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(duration) = self.duration else {
+            return Ok(());
+        };
+
+        write!(f, "{}", format_duration(duration.as_secs_f64() * 1000.0))?;
+        if let Some(budget) = self.budget {
+            write!(f, " / {} ms budget", budget.as_millis())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PatchField for StepTiming {
+    fn patch_field(
+        &mut self,
+        new: Self,
+        path: &StorePath,
+        notify: &mut dyn FnMut(&StorePath),
+        _keys: Option<&KeyMap>,
+    ) {
+        if *self != new {
+            *self = new;
+            notify(path);
+        }
+    }
+}
+
+impl PatchField for DomDiff {
+    fn patch_field(
+        &mut self,
+        new: Self,
+        path: &StorePath,
+        notify: &mut dyn FnMut(&StorePath),
+        _keys: Option<&KeyMap>,
+    ) {
+        if *self != new {
+            *self = new;
+            notify(path);
+        }
+    }
+}
+
+impl PatchField for StepLogs {
+    fn patch_field(
+        &mut self,
+        new: Self,
+        path: &StorePath,
+        notify: &mut dyn FnMut(&StorePath),
+        _keys: Option<&KeyMap>,
+    ) {
+        if *self != new {
+            *self = new;
+            notify(path);
+        }
+    }
 }
 
 /// Model describing a state of the test view
@@ -134,14 +235,15 @@ struct TestViewModel {
     /// state of the test run
     ///
     /// This vector always contains the same amount elements as the number of
-    /// steps in the [Play] for which the [TestView] was created. There is one
-    /// to one correspondence between steps and values in this vector.
+    /// steps in the [Play] for which the [TestView] was created, including the
+    /// setup and teardown steps. There is one to one correspondence between steps
+    /// and values in this vector.
     #[store(key: usize = |state| state.test_id)]
     step_results: Vec<TestState>,
     /// next step to be executed
     ///
     /// Contains the index of the next step in the [step_results][TestViewModel::step_results] and
-    /// by the extension index of the step in the [Play::steps()]
+    /// by the extension index of the step in the [schedule] of the play
     next_step: usize,
     /// execution mode
     ///
@@ -155,14 +257,16 @@ struct TestViewModel {
 
 impl TestViewModel {
     /// Create new instance of the TestViewModel
-    fn new<P: Play>(play: &P) -> Self {
-        let steps_count: usize = play.steps().len();
+    fn new(steps_count: usize) -> Self {
         let mut result: Vec<TestState> = Vec::with_capacity(steps_count);
 
         for i in 0..steps_count {
             result.push(TestState {
                 test_id: i,
                 result: TestResult::NotRun,
+                changes: DomDiff::default(),
+                logs: StepLogs::default(),
+                timing: StepTiming::default(),
             });
         }
 
@@ -175,6 +279,242 @@ impl TestViewModel {
     }
 }
 
+/// Default delay between the steps in milliseconds when the play is played
+const DEFAULT_DELAY: u64 = 500;
+
+/// Speeds available in the test viewer, as the label and the delay between steps in milliseconds
+pub(crate) const SPEEDS: [(&str, u64); 5] = [
+    ("0.25x", 2000),
+    ("0.5x", 1000),
+    ("1x", DEFAULT_DELAY),
+    ("2x", 250),
+    ("Instant", 0),
+];
+
+/// Number of the runs started by the "Run 20 times" button
+pub(crate) const REPEATED_RUNS: usize = 20;
+
+/// Controls the lifetime of the story instance shown on the canvas
+///
+/// Every time the story is reset, the [generation][StoryLifecycle::track] changes.
+/// The view of the story should be rebuilt from scratch with a new instance of
+/// the story created by [IntoStory::into_story][crate::IntoStory::into_story], which
+/// remounts the canvas and resets the state of all of the [TestView]s.
+///
+/// It also keeps the playback settings, like the speed and the breakpoints,
+/// since they must survive the reset.
+#[derive(Debug, Clone, Copy)]
+pub struct StoryLifecycle {
+    /// Incremented every time the story is reset
+    generation: RwSignal<usize>,
+    /// If `true`, the story is reset before a play is started on a canvas which was already used
+    reset_before_run: RwSignal<bool>,
+    /// `true` if any step was run since the last reset
+    dirty: RwSignal<bool>,
+    /// Number of plays which are running right now
+    running: RwSignal<usize>,
+    /// Play which should be started right after the story is reset
+    autoplay: RwSignal<Option<(usize, ExecutionMode)>>,
+    /// Delay between the steps in milliseconds when the play is played
+    delay: RwSignal<u64>,
+    /// Steps before which the played play is paused, as `(play, step)` pairs
+    ///
+    /// Breakpoints are kept here, so they survive the reset of the story
+    breakpoints: RwSignal<HashSet<(usize, usize)>>,
+    /// How long it took from mounting the story to the first rendered frame in milliseconds
+    render_ms: RwSignal<Option<f64>>,
+    /// Query selecting the plays shown in the test viewer, see [PlayFilter::parse][crate::runner::PlayFilter::parse]
+    ///
+    /// Filter is kept here, so it survives the reset of the story
+    filter: RwSignal<String>,
+    /// Path of the story, which together with the description of the play identifies its [PlayHistory]
+    path: RwSignal<String>,
+    /// Play which is run repeatedly and the number of the runs left after the current one
+    repeat: RwSignal<Option<(usize, usize)>>,
+}
+
+impl StoryLifecycle {
+    /// Create new instance of the StoryLifecycle
+    pub fn new() -> Self {
+        Self {
+            generation: RwSignal::new(0),
+            reset_before_run: RwSignal::new(true),
+            dirty: RwSignal::new(false),
+            running: RwSignal::new(0),
+            autoplay: RwSignal::new(None),
+            delay: RwSignal::new(DEFAULT_DELAY),
+            breakpoints: RwSignal::new(HashSet::new()),
+            render_ms: RwSignal::new(None),
+            filter: RwSignal::new(String::new()),
+            path: RwSignal::new(String::new()),
+            repeat: RwSignal::new(None),
+        }
+    }
+
+    /// Tracks the generation of the story
+    ///
+    /// Call it in the reactive context which builds the story view, so it will
+    /// be rebuilt after every reset.
+    pub fn track(&self) {
+        self.generation.track();
+    }
+
+    /// Measures how long it takes to render the story which is being mounted
+    ///
+    /// Call it right before the view of the story is built. The time is measured
+    /// until the browser is about to paint the next frame.
+    pub fn measure_render(&self) {
+        let render_ms = self.render_ms;
+        let start = now();
+
+        render_ms.set(None);
+        request_animation_frame(move || render_ms.set(Some(now() - start)));
+    }
+
+    /// Returns `true` if the story can be reset right now
+    ///
+    /// Story can't be reset while any of its plays is running, because the steps
+    /// still hold the old instance of the story.
+    fn can_reset(&self) -> bool {
+        self.running.get() == 0
+    }
+
+    /// Resets the story
+    ///
+    /// Does nothing if any play is running.
+    pub fn reset(&self) {
+        if self.running.get_untracked() == 0 {
+            self.dirty.set(false);
+            self.generation.update(|generation| *generation += 1);
+        }
+    }
+
+    /// Plays the play with the given index as soon as its canvas is mounted
+    ///
+    /// It's used to start the play selected in the URL.
+    pub(crate) fn play_on_mount(&self, play: usize) {
+        self.autoplay.set(Some((play, ExecutionMode::Play)));
+    }
+
+    /// Sets the delay between the steps in milliseconds when the play is played
+    pub(crate) fn set_delay(&self, delay: u64) {
+        self.delay.set(delay);
+    }
+
+    /// Query selecting the plays shown in the test viewer
+    pub(crate) fn filter(&self) -> RwSignal<String> {
+        self.filter
+    }
+
+    /// Sets the path of the story, under which the history of its plays is kept
+    pub(crate) fn set_path(&self, path: String) {
+        self.path.set(path);
+    }
+
+    /// Resets the story and starts a given play as soon as the new canvas is mounted
+    fn reset_and_run(&self, play: usize, mode: ExecutionMode) {
+        if self.running.get_untracked() == 0 {
+            self.autoplay.set(Some((play, mode)));
+            self.reset();
+        }
+    }
+
+    /// Returns `true` if the story must be reset before a play is started from the first step
+    fn needs_reset(&self) -> bool {
+        self.reset_before_run.get_untracked() && self.dirty.get_untracked()
+    }
+
+    /// Marks the beginning of the play run
+    fn begin(&self) {
+        self.dirty.set(true);
+        self.running.update(|running| *running += 1);
+    }
+
+    /// Marks the end of the play run
+    fn end(&self) {
+        self.running
+            .update(|running| *running = running.saturating_sub(1));
+    }
+
+    /// Returns `true` if there is a breakpoint before the `step` of the `play`
+    fn has_breakpoint(&self, play: usize, step: usize) -> bool {
+        self.breakpoints
+            .with(|breakpoints| breakpoints.contains(&(play, step)))
+    }
+
+    /// Sets or removes the breakpoint before the `step` of the `play`
+    fn toggle_breakpoint(&self, play: usize, step: usize) {
+        self.breakpoints.update(|breakpoints| {
+            if !breakpoints.remove(&(play, step)) {
+                breakpoints.insert((play, step));
+            }
+        });
+    }
+}
+
+impl Default for StoryLifecycle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Buttons allowing to reset the story, and the options of the reset and playback
+#[component]
+pub fn StoryLifecycleControls(
+    /// Lifecycle of the story
+    lifecycle: StoryLifecycle,
+) -> impl IntoView {
+    let reset_before_run = lifecycle.reset_before_run;
+    let delay = lifecycle.delay;
+    let render = move || {
+        lifecycle
+            .render_ms
+            .get()
+            .map(|render_ms| format!("Rendered in {}", format_duration(render_ms)))
+    };
+
+    let speeds = SPEEDS
+        .iter()
+        .map(|(label, value)| {
+            let value = *value;
+            view! {
+                <option value=value.to_string() selected=move || delay.get() == value>{*label}</option>
+            }
+        })
+        .collect_view();
+
+    let set_speed = move |ev| {
+        if let Ok(value) = event_target_value(&ev).parse::<u64>() {
+            delay.set(value);
+        }
+    };
+
+    view! {
+        <div class="leptos-forge-story-lifecycle flex flex-row items-center pb-2">
+            <label class="flex-none px-2 py-2">
+                "Speed"
+                <select class="ml-2" on:change=set_speed>{speeds}</select>
+            </label>
+            <label class="flex-none px-2 py-2">
+                <input
+                    type="checkbox"
+                    class="mr-2"
+                    prop:checked=move || reset_before_run.get()
+                    on:change=move |ev| reset_before_run.set(event_target_checked(&ev))
+                />
+                "Reset before each run"
+            </label>
+            <div class="leptos-forge-render-time flex-none px-2 py-2">{render}</div>
+            <div class="grow-1" inner_html="&nbsp;"/>
+            <button
+                on:click=move |_| lifecycle.reset()
+                disabled=move || !lifecycle.can_reset()
+                {..test_view_button_class("flex-none")}
+            >Reset</button>
+        </div>
+    }
+}
+
 /// Describes a section of the test viewer
 pub struct TestView<S>
 where
@@ -188,6 +528,12 @@ where
     state: Store<TestViewModel>,
     /// canvas where elements are drawn
     canvas: NodeRef<Div>,
+    /// lifetime of the story instance shown on the canvas
+    lifecycle: StoryLifecycle,
+    /// values passed between the steps of the play
+    scratch: StoredValue<Scratch, LocalStorage>,
+    /// results of the last runs of the play
+    history: RwSignal<PlayHistory>,
 }
 
 impl<S> TestView<S>
@@ -199,23 +545,30 @@ where
     /// # Panics
     ///
     /// Will panic if the `play` is out of bounds for the `story.plays()`.
-    pub fn new(story: S, play: usize, canvas: NodeRef<Div>) -> Self {
-        let plays = story.plays();
-        let play_to_run = plays.get(play).unwrap();
+    pub fn new(story: S, play: usize, canvas: NodeRef<Div>, lifecycle: StoryLifecycle) -> Self {
+        let steps = play_schedule(&story, play);
 
-        let state: Store<TestViewModel> = Store::new(TestViewModel::new(play_to_run));
+        let state: Store<TestViewModel> = Store::new(TestViewModel::new(steps.len()));
+        let scratch = StoredValue::new_local(Scratch::default());
+        let description = story.plays()[play].description();
+        let history = lifecycle
+            .path
+            .with_untracked(|path| RwSignal::new(PlayHistory::load(path, description)));
 
         Self {
             story,
             play,
             state,
             canvas,
+            lifecycle,
+            scratch,
+            history,
         }
     }
 }
 
 /// Css classes of the buttons used in the test viewer to play tests
-fn test_view_button_class<S: ToString>(extra_classes: S) -> impl Attribute {
+pub(crate) fn test_view_button_class<S: ToString>(extra_classes: S) -> impl Attribute {
     let class = format!(
         "leptos-forge-test-view-button bg-forgegray-300 -300 hover:bg-forgeblue-400 active:bg-forgeblue-600 active:text-forgegray-200 px-2 py-2 {}",
         extra_classes.to_string()
@@ -226,59 +579,189 @@ fn test_view_button_class<S: ToString>(extra_classes: S) -> impl Attribute {
     }
 }
 
+/// Returns the steps of the play with the given index together with its setup and teardown steps
+///
+/// # Panics
+///
+/// Will panic if the `play` is out of bounds for the `story.plays()`.
+fn play_schedule<S: Story>(story: &S, play: usize) -> Vec<ScheduledStep<S>> {
+    let plays = story.plays();
+    let play_to_run = plays.get(play).unwrap();
+
+    schedule(story, play_to_run.as_ref())
+}
+
+/// Runs the step with index `idx` and records the changes it made in the canvas
+///
+/// The changes, the console output and the duration of the step are stored next
+/// to the result of the step, so they can be shown in the test viewer. The
+/// changes include the ones rendered in reaction to the step.
+async fn execute_recorded<S: Story>(
+    state: Store<TestViewModel>,
+    story: &mut S,
+    scratch: &Scratch,
+    steps: &[ScheduledStep<S>],
+    idx: usize,
+    canvas: &HtmlElement,
+) -> StepResult {
+    let recorder = MutationRecorder::start(canvas);
+    let capture = ConsoleCapture::start();
+
+    let step = steps[idx].step.as_ref();
+    let mut ctx = StepContext::with_scratch(canvas.clone(), *story, scratch.clone());
+    let outcome = execute_step(step, &mut ctx).await;
+    // The step is measured together with the render it has triggered, so by now
+    // the effects have written its changes to the DOM
+    *story = *ctx.story();
+
+    let step_state = state.step_results().at_unkeyed(idx);
+    step_state.timing().patch(StepTiming {
+        duration: Some(Duration::from_secs_f64(
+            outcome.duration_ms.max(0.0) / 1000.0,
+        )),
+        budget: step.budget(),
+    });
+    step_state.logs().patch(capture.finish());
+    if let Some(recorder) = recorder {
+        step_state.changes().patch(recorder.finish());
+    }
+
+    outcome.result
+}
+
+/// Marks the step which was running when the panic happened as failed
+///
+/// Panicking step never completes, so the play can't continue. The play is
+/// marked as failed and the lifecycle is told that it's no longer running.
+///
+/// It's only a best effort, the panic aborts the WebAssembly module, see
+/// [on_panic]. The failure asks the user to reload the page.
+fn panicked(state: Store<TestViewModel>, lifecycle: StoryLifecycle, logs: StepLogs) {
+    let message = logs.panic().unwrap_or_default().to_string();
+    let failure = TestResult::Failure(StepFailure::new("Step has panicked").with_details(format!(
+        "```text\n{message}\n```\n\n\
+         The panic has stopped the test runner, reload the page before running the next play."
+    )));
+
+    let running = state.step_results().with_untracked(|steps| {
+        steps
+            .iter()
+            .position(|step| step.result == TestResult::InProgress)
+    });
+
+    if let Some(idx) = running {
+        let step_state = state.step_results().at_unkeyed(idx);
+        step_state.logs().patch(logs);
+        step_state.result().patch(failure.clone());
+    }
+
+    state.result().patch(failure);
+    state.mode().patch(ExecutionMode::Stopped);
+    lifecycle.end();
+}
+
+/// Runs the teardown steps starting from the step with index `from`
+///
+/// Teardown steps are run even if the play has failed or was stopped, so it doesn't
+/// leak the state into the next play. Steps which are not teardown steps are left
+/// untouched.
+///
+/// - `state`: The store containing the test view model
+/// - `story`: The mutable reference to the story being tested
+/// - `scratch`: Values passed between the steps of the play
+/// - `steps`: The list of steps in current play
+/// - `from`: Index of the first step to consider
+/// - `canvas`: The element where user widgets are being drawn
+async fn run_teardown<S: Story>(
+    state: Store<TestViewModel>,
+    story: &mut S,
+    scratch: &Scratch,
+    steps: &[ScheduledStep<S>],
+    from: usize,
+    canvas: &HtmlElement,
+) {
+    for (idx, scheduled) in steps.iter().enumerate().skip(from) {
+        if !scheduled.teardown {
+            continue;
+        }
+
+        let step_result = state.step_results().at_unkeyed(idx).result();
+        step_result.patch(TestResult::InProgress);
+
+        match execute_recorded(state, story, scratch, steps, idx, canvas).await {
+            Ok(()) => step_result.patch(TestResult::Success),
+            Err(e) => step_result.patch(TestResult::Failure(e)),
+        }
+    }
+}
+
 /// Runs one step of the tests and updates the UI state accordingly
 ///
+/// If the step is still in progress (for example an asynchronous step is waiting
+/// for something), then this function does nothing. The element the step has
+/// acted on is outlined in the canvas, the outline of the failed step is kept.
+///
 /// - `state`: The store containing the test view model
 /// - `story`: The mutable reference to the story being tested
+/// - `scratch`: Values passed between the steps of the play
 /// - `steps`: The list of steps in current play
 /// - `canvas`: The reference to the area where user widgets are being drawn
-fn run_one_step<S: Story>(
+async fn run_one_step<S: Story>(
     state: Store<TestViewModel>,
     story: &mut S,
-    steps: &[Box<dyn Step<Story = S>>],
+    scratch: &Scratch,
+    steps: &[ScheduledStep<S>],
     canvas: NodeRef<Div>,
 ) {
     let next_step: usize = state.next_step().get_untracked();
 
     if next_step < steps.len() {
+        let step_result = state.step_results().at_unkeyed(next_step).result();
+
+        if TestResult::InProgress == step_result.get_untracked() {
+            return;
+        }
+
         if TestResult::InProgress != state.result().get_untracked() {
             state.result().patch(TestResult::InProgress);
         }
 
-        state
-            .step_results()
-            .at_unkeyed(next_step)
-            .result()
-            .patch(TestResult::InProgress);
-
-        canvas.with(|canvas| {
-            if let Some(canvas) = canvas {
-                if let Err(e) = steps[next_step].run(canvas, story) {
-                    let failure = TestResult::Failure(e);
-                    state
-                        .step_results()
-                        .at_unkeyed(next_step)
-                        .result()
-                        .patch(failure);
-                    state.result().patch(failure);
-                } else {
-                    state
-                        .step_results()
-                        .at_unkeyed(next_step)
-                        .result()
-                        .patch(TestResult::Success);
-                    state.next_step().patch(next_step + 1);
-                };
+        step_result.patch(TestResult::InProgress);
+
+        if let Some(canvas) = canvas.get_untracked() {
+            let canvas: &HtmlElement = &canvas;
+
+            take_targets();
+            let outcome = execute_recorded(state, story, scratch, steps, next_step, canvas).await;
+            let targets = take_targets();
+            if targets.is_empty() {
+                clear_highlight();
             } else {
-                let failure = TestResult::Failure("Unable to get the reference to the canvas");
-                state
-                    .step_results()
-                    .at_unkeyed(next_step)
-                    .result()
-                    .patch(failure);
-                state.result().patch(failure);
+                highlight(
+                    canvas,
+                    &targets,
+                    steps[next_step].step.description(),
+                    outcome.is_err(),
+                );
             }
-        });
+
+            if let Err(e) = outcome {
+                let failure = TestResult::Failure(e);
+                step_result.patch(failure.clone());
+                state.result().patch(failure);
+
+                run_teardown(state, story, scratch, steps, next_step + 1, canvas).await;
+            } else {
+                step_result.patch(TestResult::Success);
+                state.next_step().patch(next_step + 1);
+            };
+        } else {
+            let failure = TestResult::Failure(StepFailure::new(
+                "Unable to get the reference to the canvas",
+            ));
+            step_result.patch(failure.clone());
+            state.result().patch(failure);
+        }
     }
     let next_step: usize = state.next_step().get_untracked();
     if next_step >= steps.len() {
@@ -292,78 +775,374 @@ fn run_one_step<S: Story>(
     }
 }
 
-/// Plays the rest of steps in the test. If
+/// Stops the play before the step with index `next_step`
+///
+/// Remaining teardown steps are run and the play is marked as [stopped][TestResult::Stopped].
+async fn stop_play<S: Story>(
+    state: Store<TestViewModel>,
+    story: &mut S,
+    scratch: &Scratch,
+    steps: &[ScheduledStep<S>],
+    next_step: usize,
+    canvas: NodeRef<Div>,
+) {
+    if let Some(canvas) = canvas.get_untracked() {
+        run_teardown(state, story, scratch, steps, next_step, &canvas).await;
+    }
+
+    state.result().patch(TestResult::Stopped);
+}
+
+/// Plays the rest of steps in the test.
+///
+/// Every step is awaited before the runner moves to the next one. Between the steps
+/// the runner waits for the delay selected in the test viewer. The play is paused
+/// before every step with a breakpoint, and while it's [paused][ExecutionMode::Paused]
+/// the runner waits until it's resumed or [stopped][ExecutionMode::Stopped].
 ///
-/// - `delay`: The number of milliseconds to wait between steps
 /// - `state`: The store containing the test view model
-/// - `story`: The mutable reference to the story being tested
-/// - `steps`: The list of steps in current play
+/// - `story`: The story being tested
+/// - `scratch`: Values passed between the steps of the play
+/// - `play`: index of the play with a test to run (inside of the story)
 /// - `canvas`: The reference to the area where user widgets are being drawn
-fn play_steps<S: 'static + Story>(
-    delay: u64,
+/// - `lifecycle`: Lifetime of the story instance, which keeps the playback settings
+async fn play_steps<S: 'static + Story>(
     state: Store<TestViewModel>,
     mut story: S,
+    scratch: Scratch,
     play: usize,
     canvas: NodeRef<Div>,
+    lifecycle: StoryLifecycle,
 ) {
-    let plays: Vec<Box<dyn Play<Story = S> + 'static>> = story.plays();
-    let play_to_run: &dyn Play<Story = S> = plays.get(play).unwrap();
-    let steps: Vec<Box<dyn Step<Story = S> + 'static>> = play_to_run.steps();
-    let next_step: usize = state.next_step().get_untracked();
-    let result: TestResult = state.result().get_untracked();
+    let steps: Vec<ScheduledStep<S>> = play_schedule(&story, play);
+
+    // the breakpoint at which the play was paused, so it doesn't pause again after resume
+    let mut paused_at: Option<usize> = None;
+
+    state.mode().patch(ExecutionMode::Play);
+
+    loop {
+        let next_step: usize = state.next_step().get_untracked();
+        let result: TestResult = state.result().get_untracked();
+
+        if next_step >= steps.len() || result.is_complete() {
+            break;
+        }
+
+        match state.mode().get_untracked() {
+            ExecutionMode::Stopped => {
+                stop_play(state, &mut story, &scratch, &steps, next_step, canvas).await;
+                break;
+            }
+            ExecutionMode::Paused => {
+                sleep(POLL_INTERVAL).await;
+                continue;
+            }
+            ExecutionMode::Play | ExecutionMode::Step => {}
+        }
+
+        if paused_at != Some(next_step) && lifecycle.has_breakpoint(play, next_step) {
+            paused_at = Some(next_step);
+            state.mode().patch(ExecutionMode::Paused);
+            continue;
+        }
 
-    if next_step < steps.len() && !result.is_complete() {
-        run_one_step(state, &mut story, &steps, canvas);
+        run_one_step(state, &mut story, &scratch, &steps, canvas).await;
 
         let result: TestResult = state.result().get_untracked();
 
-        if !result.is_complete() {
-            set_timeout(
-                move || {
-                    play_steps(delay, state, story, play, canvas);
-                },
-                Duration::from_millis(delay),
-            );
+        if result.is_complete() {
+            break;
         }
+
+        sleep(Duration::from_millis(lifecycle.delay.get_untracked())).await;
+    }
+
+    state.mode().patch(ExecutionMode::Stopped);
+}
+
+/// Runs the play in the given execution mode
+///
+/// - `mode`: [ExecutionMode::Play] plays the rest of the steps, [ExecutionMode::Step] runs only the next one
+/// - `state`: The store containing the test view model
+/// - `story`: The story being tested
+/// - `scratch`: Values passed between the steps of the play, cleared when the play starts from the first step
+/// - `play`: index of the play with a test to run (inside of the story)
+/// - `canvas`: The reference to the area where user widgets are being drawn
+/// - `lifecycle`: Lifetime of the story instance shown on the canvas
+fn start<S: 'static + Story>(
+    mode: ExecutionMode,
+    state: Store<TestViewModel>,
+    story: S,
+    scratch: StoredValue<Scratch, LocalStorage>,
+    play: usize,
+    canvas: NodeRef<Div>,
+    lifecycle: StoryLifecycle,
+) {
+    lifecycle.begin();
+
+    if state.next_step().get_untracked() == 0 {
+        scratch.set_value(Scratch::new(now()));
     }
+    let scratch = scratch.get_value();
+
+    spawn_local(async move {
+        let _panic_guard = on_panic(move |logs| panicked(state, lifecycle, logs));
+
+        match mode {
+            ExecutionMode::Play => play_steps(state, story, scratch, play, canvas, lifecycle).await,
+            ExecutionMode::Step => {
+                let mut story = story;
+                let steps: Vec<ScheduledStep<S>> = play_schedule(&story, play);
+
+                run_one_step(state, &mut story, &scratch, &steps, canvas).await;
+            }
+            ExecutionMode::Paused | ExecutionMode::Stopped => {}
+        }
+
+        lifecycle.end();
+    });
+}
+
+/// Stops the play which is run step by step
+///
+/// Nothing is looping over the steps of the play run with the [ExecutionMode::Step],
+/// so there is nobody to notice the [stop][ExecutionMode::Stopped]. The remaining
+/// teardown steps are run right away instead.
+///
+/// - `state`: The store containing the test view model
+/// - `story`: The story being tested
+/// - `scratch`: Values passed between the steps of the play
+/// - `play`: index of the play with a test to run (inside of the story)
+/// - `canvas`: The reference to the area where user widgets are being drawn
+/// - `lifecycle`: Lifetime of the story instance shown on the canvas
+fn stop_stepping<S: 'static + Story>(
+    state: Store<TestViewModel>,
+    story: S,
+    scratch: StoredValue<Scratch, LocalStorage>,
+    play: usize,
+    canvas: NodeRef<Div>,
+    lifecycle: StoryLifecycle,
+) {
+    lifecycle.begin();
+    let scratch = scratch.get_value();
+
+    spawn_local(async move {
+        let _panic_guard = on_panic(move |logs| panicked(state, lifecycle, logs));
+
+        let mut story = story;
+        let steps: Vec<ScheduledStep<S>> = play_schedule(&story, play);
+        let next_step = state.next_step().get_untracked();
+
+        stop_play(state, &mut story, &scratch, &steps, next_step, canvas).await;
+
+        lifecycle.end();
+    });
 }
 
 impl<S: 'static + Story> DetailsParts for TestView<S> {
     fn summary(&self) -> AnyView {
         let TestView {
-            mut story,
+            story,
             play,
             state,
             canvas,
+            lifecycle,
+            scratch,
+            history,
         } = *self;
 
         let plays: Vec<Box<dyn Play<Story = S> + 'static>> = story.plays();
         let play_to_run: &dyn Play<Story = S> = plays.get(play).unwrap();
-        let steps: Vec<Box<dyn Step<Story = S> + 'static>> = play_to_run.steps();
+        let description = play_to_run.description();
+
+        // Play requested before the story was reset is started as soon as the new canvas is mounted
+        Effect::new(move || {
+            if canvas.get().is_some()
+                && let Some((autoplay, mode)) = lifecycle.autoplay.get_untracked()
+                && autoplay == play
+            {
+                lifecycle.autoplay.set(None);
+                start(mode, state, story, scratch, play, canvas, lifecycle);
+            }
+        });
+
+        // Passed and failed runs are remembered, stopped runs don't tell anything about the play
+        Effect::new(move |previous: Option<TestResult>| {
+            let result = state.result().get();
+            let finished = match result {
+                TestResult::Success => Some(true),
+                TestResult::Failure(_) => Some(false),
+                _ => None,
+            };
 
-        let play_test = move |_| {
-            play_steps(500, state, story, play, canvas);
+            if let Some(passed) = finished
+                && previous.is_some_and(|previous| !previous.is_complete())
+            {
+                history.update(|history| history.push(passed));
+
+                let path = lifecycle.path.get_untracked();
+                history.with_untracked(|history| history.save(&path, description));
+            }
+
+            result
+        });
+
+        // Repeated play is started again on a fresh story as soon as its run has ended
+        Effect::new(move || {
+            let result = state.result().get();
+            if lifecycle.running.get() == 0
+                && result.is_complete()
+                && let Some((repeated, left)) = lifecycle.repeat.get_untracked()
+                && repeated == play
+            {
+                if left > 0 && result != TestResult::Stopped {
+                    lifecycle.repeat.set(Some((play, left - 1)));
+                    lifecycle.reset_and_run(play, ExecutionMode::Play);
+                } else {
+                    lifecycle.repeat.set(None);
+                }
+            }
+        });
+
+        // Finished play, or a play which would start on the canvas used by another run, is run on a fresh story
+        let run = move |mode: ExecutionMode| {
+            let complete = state.result().get_untracked().is_complete();
+            let fresh = state.next_step().get_untracked() == 0
+                && state.result().get_untracked() == TestResult::NotRun;
+
+            if complete || (fresh && lifecycle.needs_reset()) {
+                lifecycle.reset_and_run(play, mode);
+            } else {
+                start(mode, state, story, scratch, play, canvas, lifecycle);
+            }
         };
 
-        let run_one_step = move |_| {
-            run_one_step(state, &mut story, &steps, canvas);
+        let play_test = move |_| run(ExecutionMode::Play);
+        let repeat = move |_| {
+            lifecycle.repeat.set(Some((play, REPEATED_RUNS - 1)));
+            run(ExecutionMode::Play);
+        };
+        let run_one_step = move |_| run(ExecutionMode::Step);
+        let pause = move |_| state.mode().patch(ExecutionMode::Paused);
+        let resume = move |_| state.mode().patch(ExecutionMode::Play);
+        let stop = move |_| state.mode().patch(ExecutionMode::Stopped);
+        let stop_steps = move |_| stop_stepping(state, story, scratch, play, canvas, lifecycle);
+        // Play run step by step can be stopped between the steps, which runs its teardown
+        let stepping = move || {
+            lifecycle.can_reset()
+                && state.next_step().get() > 0
+                && !state.result().get().is_complete()
         };
 
         let result = move || format!("{}", state.result().get());
+        let tags = play_to_run
+            .tags()
+            .iter()
+            .map(|tag| format!("#{tag}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let duration = move || {
+            let total = state.step_results().with(|steps| {
+                steps
+                    .iter()
+                    .filter_map(|step| step.timing.duration)
+                    .sum::<Duration>()
+            });
+
+            let total_ms = total.as_secs_f64() * 1000.0;
+
+            (!total.is_zero()).then(|| format!("({})", format_duration(total_ms)))
+        };
+        let repetition = move || {
+            lifecycle.repeat.get().and_then(|(repeated, left)| {
+                (repeated == play)
+                    .then(|| format!("Run {} of {REPEATED_RUNS}", REPEATED_RUNS - left))
+            })
+        };
+        let sparkline = move || history.with(PlayHistory::sparkline);
+        let pass_rate = move || history.with(PlayHistory::summary);
+        let flaky = move || {
+            history.with(PlayHistory::is_flaky).then(|| {
+                view! {
+                    <span
+                        class="leptos-forge-test-viewer-flaky px-2 text-red-700 font-bold"
+                        title="Outcome of the play varies between the runs"
+                    >"flaky"</span>
+                }
+            })
+        };
+
+        let controls = move || match state.mode().get() {
+            ExecutionMode::Play => view! {
+                <button
+                    on:click={pause}
+                    {..test_view_button_class("")}
+                >Pause</button>
+                <button
+                    on:click={stop}
+                    {..test_view_button_class("")}
+                >Stop</button>
+            }
+            .into_any(),
+            ExecutionMode::Paused => view! {
+                <button
+                    on:click={resume}
+                    {..test_view_button_class("")}
+                >Resume</button>
+                <button
+                    on:click={run_one_step}
+                    {..test_view_button_class("")}
+                >Step</button>
+                <button
+                    on:click={stop}
+                    {..test_view_button_class("")}
+                >Stop</button>
+            }
+            .into_any(),
+            ExecutionMode::Step | ExecutionMode::Stopped => view! {
+                <button
+                    on:click={play_test}
+                    {..test_view_button_class("")}
+                >Play</button>
+                <button
+                    on:click={run_one_step}
+                    {..test_view_button_class("")}
+                >Step</button>
+                <Show when=stepping>
+                    <button
+                        on:click={stop_steps}
+                        title="Stop the play and run its teardown steps"
+                        {..test_view_button_class("")}
+                    >Stop</button>
+                </Show>
+                <button
+                    on:click={repeat}
+                    title="Play the play on a fresh story 20 times in a row"
+                    {..test_view_button_class("")}
+                >Run 20 times</button>
+            }
+            .into_any(),
+        };
 
         (view!{
             <div class="leptos-forge-test-viewer flex flex-row bg-forgegray-100 items-center ">
                 <div class="leptos-forge-test-viewer-test-name flex-none text-base font-bold px-2 py-2">{result} - {play_to_run.description()}</div>
+                <div class="leptos-forge-test-viewer-tags flex-none px-2 py-2 text-forgegray-400">{tags}</div>
+                <div class="leptos-forge-test-viewer-duration flex-none px-2 py-2">{duration}</div>
+                <div
+                    class="leptos-forge-test-viewer-history flex-none px-2 py-2"
+                    class:hidden=move || history.with(PlayHistory::is_empty)
+                    title="Results of the last runs, the oldest first"
+                >
+                    <span class="leptos-forge-test-viewer-sparkline font-mono text-forgegray-400">{sparkline}</span>
+                    <span class="px-2">{pass_rate}</span>
+                    {flaky}
+                </div>
+                <div class="leptos-forge-test-viewer-repetition flex-none px-2 py-2">{repetition}</div>
                 <div class="grow-1" inner_html="&nbsp;"/>
                 <div class="leptos-forge-test-viewer-controls flex-none">
-                    <button
-                        on:click={play_test}
-                        {..test_view_button_class("")}
-                    >Play</button>
-                    <button
-                        on:click={run_one_step}
-                        {..test_view_button_class("")}
-                    >Step</button>
+                    {controls}
                 </div>
             </div>
         }).into_any()
@@ -373,12 +1152,10 @@ impl<S: 'static + Story> DetailsParts for TestView<S> {
         let play = self.play;
         let story = self.story;
         let store = self.state;
-        let plays = story.plays();
-        let play_to_run = plays.get(play).unwrap();
-        let steps = play_to_run
-            .steps()
+        let lifecycle = self.lifecycle;
+        let steps = play_schedule(&story, play)
             .iter()
-            .map(|step| step.description())
+            .map(|scheduled| scheduled.step.description())
             .collect::<Vec<_>>();
 
         (view! {
@@ -399,6 +1176,8 @@ impl<S: 'static + Story> DetailsParts for TestView<S> {
                         }
                     }
                     state={result}
+                    play
+                    lifecycle
                 />
                 </For>
             </ul>
@@ -415,14 +1194,95 @@ fn StepView<S: ToString>(
     /// State of the state view
     #[prop(into)]
     state: Field<TestState>,
+    /// index of the play containing the step
+    play: usize,
+    /// Lifetime of the story instance, which keeps the breakpoints
+    lifecycle: StoryLifecycle,
 ) -> impl IntoView {
+    let step = state.test_id().get_untracked();
+    let breakpoint_class = move || {
+        if lifecycle
+            .breakpoints
+            .with(|breakpoints| breakpoints.contains(&(play, step)))
+        {
+            "leptos-forge-test-breakpoint cursor-pointer pr-2 text-red-700"
+        } else {
+            "leptos-forge-test-breakpoint cursor-pointer pr-2 text-forgegray-300 hover:text-red-300"
+        }
+    };
+    let toggle_breakpoint = move |_| lifecycle.toggle_breakpoint(play, step);
+
     let test_state = state.result();
-    let test_state = move || format!("{}", test_state.get());
+    let test_result = move || format!("{}", test_state.get());
+    let failure = move || {
+        if let TestResult::Failure(failure) = test_state.get() {
+            Some(view! {
+                <div class="leptos-forge-test-failure markdown pl-4">
+                    <Markdown src={failure.to_markdown()} />
+                </div>
+            })
+        } else {
+            None
+        }
+    };
+
+    let changes = state.changes();
+    let changes = move || {
+        changes.with(|changes| {
+            (!changes.is_empty()).then(|| {
+                let summary = format!("DOM changes ({})", changes.changes.len());
+                let markdown = changes.to_markdown();
+                view! {
+                    <details class="leptos-forge-test-dom-changes pl-4">
+                        <summary class="cursor-pointer">{summary}</summary>
+                        <div class="markdown pl-4">
+                            <Markdown src={markdown} />
+                        </div>
+                    </details>
+                }
+            })
+        })
+    };
+
+    let logs = state.logs();
+    let logs = move || {
+        logs.with(|logs| {
+            (!logs.is_empty()).then(|| {
+                let summary = format!("Console ({})", logs.entries.len());
+                let markdown = logs.to_markdown();
+                view! {
+                    <details class="leptos-forge-test-console pl-4">
+                        <summary class="cursor-pointer">{summary}</summary>
+                        <div class="markdown pl-4">
+                            <Markdown src={markdown} />
+                        </div>
+                    </details>
+                }
+            })
+        })
+    };
+
+    let timing = state.timing();
+    let timing = move || {
+        let timing = timing.get();
+        timing.duration.is_some().then(|| format!("({timing})"))
+    };
 
     let description = description.to_string();
 
     view! {
-        <li>{test_state} - {description}</li>
+        <li>
+            <span
+                class=breakpoint_class
+                title="Pause the play before this step"
+                on:click=toggle_breakpoint
+            >"●"</span>
+            {test_result} - {description}
+            <span class="leptos-forge-test-timing pl-2 text-forgegray-700">{timing}</span>
+            {failure}
+            {logs}
+            {changes}
+        </li>
     }
 }
 
@@ -2,10 +2,7 @@
 
 use forge::RouteDef;
 use leptos::prelude::*;
-use leptos::web_sys::HtmlElement;
 
-use testing_library_dom::MatcherOptions;
-use testing_library_dom::get_by_test_id;
 use ui_components::primitives::input::TextInput;
 use ui_components::primitives::label::InlineFieldLabel;
 use ui_components::primitives::label::TextFieldLabel;
@@ -15,6 +12,10 @@ use utils_leptos::signal::URwSignal;
 use forge::Story;
 use forge::story::Play;
 use forge::story::Step;
+use forge::story::StepContext;
+use forge::story::StepFailure;
+use forge::story::StepResult;
+use forge::testing::get_by_test_id;
 
 /// Description of the label primitive
 const LABEL_DESC: &str = r############"
@@ -144,9 +145,9 @@ impl Step for BasicLabelStoryPlayUpdateFromEmptyStepInit {
         "Initialize the label to an empty string"
     }
 
-    fn run(&self, _canvas: &HtmlElement, story: &mut Self::Story) -> Result<(), &'static str> {
-        story.label.set(String::default());
-        story.text.set(String::default());
+    fn run(&self, ctx: &mut StepContext<Self::Story>) -> StepResult {
+        ctx.story().label.set(String::default());
+        ctx.story().text.set(String::default());
         Ok(())
     }
 }
@@ -161,23 +162,21 @@ impl Step for BasicLabelStoryPlayUpdateFromEmptyStep1 {
         "Check initial condition of the label"
     }
 
-    fn run(&self, canvas: &HtmlElement, story: &mut Self::Story) -> Result<(), &'static str> {
-        if !story.label.get_untracked().is_empty() {
-            return Err("Label signal should be empty");
+    fn run(&self, ctx: &mut StepContext<Self::Story>) -> StepResult {
+        if !ctx.story().label.get_untracked().is_empty() {
+            return Err("Label signal should be empty".into());
         }
 
-        if !story.text.get_untracked().is_empty() {
-            return Err("Text signal should be empty");
+        if !ctx.story().text.get_untracked().is_empty() {
+            return Err("Text signal should be empty".into());
         }
 
-        let Ok(label) = get_by_test_id(canvas, "label", MatcherOptions::default()) else {
-            return Err("Label should be present");
-        };
+        let label = get_by_test_id(ctx.canvas(), "label")?;
 
         let inner_text = label.inner_text();
 
         if !inner_text.is_empty() {
-            return Err("Label should not have any text");
+            return Err("Label should not have any text".into());
         }
 
         Ok(())
@@ -194,8 +193,8 @@ impl Step for BasicLabelStoryPlayUpdateFromEmptyStep2 {
         "Update label from empty state"
     }
 
-    fn run(&self, _canvas: &HtmlElement, story: &mut Self::Story) -> Result<(), &'static str> {
-        story.label.set("New label".to_string());
+    fn run(&self, ctx: &mut StepContext<Self::Story>) -> StepResult {
+        ctx.story().label.set("New label".to_string());
 
         Ok(())
     }
@@ -211,25 +210,31 @@ impl Step for BasicLabelStoryPlayUpdateFromEmptyStep3 {
         "Check if signal events resulted in the content being updated"
     }
 
-    fn run(&self, canvas: &HtmlElement, story: &mut Self::Story) -> Result<(), &'static str> {
-        let Ok(label) = get_by_test_id(canvas, "label", MatcherOptions::default()) else {
-            return Err("Label should be present");
-        };
+    fn run(&self, ctx: &mut StepContext<Self::Story>) -> StepResult {
+        let label = get_by_test_id(ctx.canvas(), "label")?;
 
         let inner_text = label.inner_text();
 
         if inner_text.is_empty() {
-            return Err("Label should be updated");
+            return Err("Label should be updated".into());
         }
 
         if inner_text != "New label" {
-            return Err("Label should have a value `New label`");
+            return Err(StepFailure::mismatch(
+                "Label has a wrong value",
+                "New label",
+                inner_text,
+            ));
         }
 
-        let signal_value = story.label.get_untracked();
+        let signal_value = ctx.story().label.get_untracked();
 
         if signal_value != "New label" {
-            return Err("Signal should have a value `New label`");
+            return Err(StepFailure::mismatch(
+                "Signal has a wrong value",
+                "New label",
+                signal_value,
+            ));
         }
 
         Ok(())
@@ -246,9 +251,9 @@ impl Step for BasicLabelStoryPlayUpdateFromEmptyStepCleanup {
         "Cleanup after tests"
     }
 
-    fn run(&self, _canvas: &HtmlElement, story: &mut Self::Story) -> Result<(), &'static str> {
-        story.label.set("Label".to_string());
-        story.text.set("Text".to_string());
+    fn run(&self, ctx: &mut StepContext<Self::Story>) -> StepResult {
+        ctx.story().label.set("Label".to_string());
+        ctx.story().text.set("Text".to_string());
         Ok(())
     }
 }
@@ -46,9 +46,10 @@ impl Section for Primitives {
                 path: "menu",
                 label: "Menu",
                 component: || view! {"Menu"}.into_any(),
-                embedded: |_, _, _| view! {"Embedded menu"}.into_any(),
+                embedded: |_, _, _, _| view! {"Embedded menu"}.into_any(),
                 subroutes: vec![],
                 private: false,
+                suite: || None,
             },
             RouteDef::story::<BasicSwitchStory>("switch", "Switch"),
         ]
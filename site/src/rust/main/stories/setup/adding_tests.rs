@@ -2,15 +2,7 @@
 //! [setup process][super::SETUP] and it's later refinement [`CounterStory`][super::refine_story::CounterStory]
 //!
 
-use testing_library_dom::MatcherOptions;
-use testing_library_dom::SelectorMatcherOptions;
-use testing_library_dom::fire_event;
-use testing_library_dom::get_by_test_id;
-use testing_library_dom::get_by_text;
-
 use leptos::prelude::*;
-use leptos::web_sys::MouseEvent;
-use leptos::web_sys::MouseEventInit;
 
 use ui_components::widgets::field::TextField;
 use utils_leptos::signal::URwSignal;
@@ -18,9 +10,13 @@ use utils_leptos::signal::URwSignal;
 use forge::Play;
 use forge::RouteDef;
 use forge::Section;
+use forge::StepFailure;
 use forge::Story;
 use forge::play;
 use forge::test_id;
+use forge::testing::click;
+use forge::testing::get_by_test_id;
+use forge::testing::get_by_text;
 
 /// description of the [Implement the first story][RefineCounterStory] section
 const ADDING_TESTS: &str = r#############"
@@ -30,9 +26,9 @@ This chapter continues from the [Implementing first story](setup/first_story)
 and is a third part of our journey to learn the `leptos_forge`.
 
 The last thing we are missing for our `CounterStory` is tests. Like Storybook,
-we call them `plays`. To write tests, we will use 
-[`testing-library-dom`](https://github.com/RustForWeb/testing-library), 
-a rewrite in Rust of the [JS TestingLibrary](https://testing-library.com).
+we call them `plays`. To find the elements in the canvas, we will use the
+queries and the user events from the `forge::testing` module, which are
+modeled after the [JS TestingLibrary](https://testing-library.com).
 
 TestingLibrary has excellent [documentation about writing maintainable interaction tests](https://testing-library.com/docs/)
 for your components.
@@ -132,15 +128,8 @@ another function from `Story::plays`. This function should return a list of play
 where each play contains a number of steps.  
 
 ```rust  
-use leptos::web_sys::{MouseEvent, MouseEventInit};
-use testing_library_dom::{
-    get_by_text,
-    get_by_test_id, 
-    fire_event,
-    MatcherOptions,
-    SelectorMatcherOptions
-};
-use forge::{Play, play};  
+use forge::{Play, StepFailure, play};  
+use forge::testing::{click, get_by_test_id, get_by_text};
 
 impl Story for TestedCounterStory {  
     ...  
@@ -155,65 +144,51 @@ impl Story for TestedCounterStory {
             play::<Self>("When the counter value crosses the threshold, a message should be shown.")  
                 .next(  
                     "Set the well-known state to the Counter",  
-                    |_canvas, story| {  
-                        story.message.set(COUNTER_PLAY_MESSAGE.to_string());  
-                        story.threshold.set(COUNTER_PLAY_THRESHOLD);  
-                        story.value.set(COUNTER_PLAY_THRESHOLD);  
+                    |ctx| {  
+                        ctx.story().message.set(COUNTER_PLAY_MESSAGE.to_string());  
+                        ctx.story().threshold.set(COUNTER_PLAY_THRESHOLD);  
+                        ctx.story().value.set(COUNTER_PLAY_THRESHOLD);  
                         Ok(())  
                     }  
                 )  
                 .next(  
                     "Check that message is **not** shown yet",  
-                    |canvas, _story| {  
-                        let Ok(message_span) = get_by_test_id(canvas, COUNTER_MESSAGE_TEST_ID, MatcherOptions::default()) else {  
-                            return Err("Unable to get a message span");  
-                        };  
+                    |ctx| {  
+                        let message_span = ctx.get_by_test_id(COUNTER_MESSAGE_TEST_ID)?;  
 
                         let message = message_span.inner_text();  
 
                         if !message.is_empty() {  
-                            return Err("Showing message, while it should be empty");  
+                            return Err("Showing message, while it should be empty".into());  
                         }  
 
                         Ok(())  
                     }  
                 )  
                 .next(  
-                    |canvas, _story| { 
-                        let Ok(button) = get_by_text(canvas, "+", SelectorMatcherOptions::default()) else {
-                            return Err("Can't find the increase button");
-                        };
-                        
-                        let mouse_event_init = MouseEventInit::new();
-                        mouse_event_init.set_bubbles(true);
-                        mouse_event_init.set_cancelable(true);
-
-                        let Ok(event) = MouseEvent::new_with_mouse_event_init_dict("click", &mouse_event_init) else {
-                            return Err("Can't create a click event");
-                        };
-                        
-                        let Ok(_) = fire_event(&button, &event) else {
-                            return Err("Event should be fired.");
-                        };
-
-                        Ok(()) 
+                    |ctx| { 
+                        let button = ctx.get_by_text("+")?;
+
+                        click(&button)
                     }  
                 )  
                 .next(  
                     "Check that counter was increased and that message **is** shown",  
-                    |canvas, _story| {  
-                        let Ok(message_span) = get_by_test_id(canvas, COUNTER_MESSAGE_TEST_ID, MatcherOptions::default()) else {  
-                            return Err("Unable to get a message span");  
-                        };  
+                    |ctx| {  
+                        let message_span = ctx.get_by_test_id(COUNTER_MESSAGE_TEST_ID)?;  
 
                         let message = message_span.inner_text();  
 
                         if message.is_empty() {  
-                            return Err("Message is not visible");  
+                            return Err("Message is not visible".into());  
                         }  
 
                         if message.as_str() != COUNTER_PLAY_MESSAGE {  
-                            return Err("Displaying wrong message");  
+                            return Err(StepFailure::mismatch(  
+                                "Displaying wrong message",  
+                                COUNTER_PLAY_MESSAGE,  
+                                message,  
+                            ));  
                         }  
 
                         Ok(())  
@@ -221,10 +196,10 @@ impl Story for TestedCounterStory {
                 )  
                 .next(  
                     "Set some sensible values to the Counter",  
-                    |_canvas, story| {  
-                        story.message.set("You work hard!".to_string());  
-                        story.threshold.set(10_000);  
-                        story.value.set(0);  
+                    |ctx| {  
+                        ctx.story().message.set("You work hard!".to_string());  
+                        ctx.story().threshold.set(10_000);  
+                        ctx.story().value.set(0);  
                         Ok(())  
                     }  
                 )  
@@ -264,6 +239,11 @@ After you press `Play`, the test will complete and you will see
 
 ![State of the UI after completing the test](/resources/leptos_forge_site/images/guides/adding_tests/04-completed.png)  
 
+You can also try it right here. The counter below is embedded together with its
+plays, so you can run them without leaving the guide.
+
+<Story of="tested_counter_story" tests />
+
 "#############;
 
 /// This section describes how you can implement your first story
@@ -432,90 +412,56 @@ impl Story for TestedCounterStory {
             const COUNTER_PLAY_THRESHOLD: i32 = 15_000;
 
             play::<Self>("When the counter value crosses the threshold, a message should be shown.")
-                .next(
-                    "Set the well known state to the Counter",
-                    |_canvas, story| {
-                        story.message.set(COUNTER_PLAY_MESSAGE.to_string());
-                        story.threshold.set(COUNTER_PLAY_THRESHOLD);
-                        story.value.set(COUNTER_PLAY_THRESHOLD);
-                        Ok(())
-                    },
-                )
-                .next(
-                    "Check that message is **not** shown yet",
-                    |canvas, _story| {
-                        let Ok(message_span) = get_by_test_id(
-                            canvas,
-                            COUNTER_MESSAGE_TEST_ID,
-                            MatcherOptions::default(),
-                        ) else {
-                            return Err("Unable to get a message span");
-                        };
-
-                        let message = message_span.inner_text();
-
-                        if !message.is_empty() {
-                            return Err("Showing message, while it should be empty");
-                        }
-
-                        Ok(())
-                    },
-                )
-                .next("Increase the counter", |canvas, _story| {
-                    let Ok(button) = get_by_text(canvas, "+", SelectorMatcherOptions::default())
-                    else {
-                        return Err("Can't find the increase button");
-                    };
-
-                    let mouse_event_init = MouseEventInit::new();
-                    mouse_event_init.set_bubbles(true);
-                    mouse_event_init.set_cancelable(true);
+                .next("Set the well known state to the Counter", |ctx| {
+                    ctx.story().message.set(COUNTER_PLAY_MESSAGE.to_string());
+                    ctx.story().threshold.set(COUNTER_PLAY_THRESHOLD);
+                    ctx.story().value.set(COUNTER_PLAY_THRESHOLD);
+                    Ok(())
+                })
+                .next("Check that message is **not** shown yet", |ctx| {
+                    let message_span = ctx.get_by_test_id(COUNTER_MESSAGE_TEST_ID)?;
 
-                    let Ok(event) =
-                        MouseEvent::new_with_mouse_event_init_dict("click", &mouse_event_init)
-                    else {
-                        return Err("Can't create a click event");
-                    };
+                    let message = message_span.inner_text();
 
-                    let Ok(_) = fire_event(&button, &event) else {
-                        return Err("Event should be fired.");
-                    };
+                    if !message.is_empty() {
+                        return Err("Showing message, while it should be empty".into());
+                    }
 
                     Ok(())
                 })
+                .next("Increase the counter", |ctx| {
+                    let button = ctx.get_by_text("+")?;
+
+                    click(&button)
+                })
                 .next(
                     "Check that counter was increased and that message **is** shown",
-                    |canvas, _story| {
-                        let Ok(message_span) = get_by_test_id(
-                            canvas,
-                            COUNTER_MESSAGE_TEST_ID,
-                            MatcherOptions::default(),
-                        ) else {
-                            return Err("Unable to get a message span");
-                        };
+                    |ctx| {
+                        let message_span = ctx.get_by_test_id(COUNTER_MESSAGE_TEST_ID)?;
 
                         let message = message_span.inner_text();
 
                         if message.is_empty() {
-                            return Err("Message is not visible");
+                            return Err("Message is not visible".into());
                         }
 
                         if message.as_str() != COUNTER_PLAY_MESSAGE {
-                            return Err("Displaying wrong message");
+                            return Err(StepFailure::mismatch(
+                                "Displaying wrong message",
+                                COUNTER_PLAY_MESSAGE,
+                                message,
+                            ));
                         }
 
                         Ok(())
                     },
                 )
-                .next(
-                    "Set some sensible values to the Counter",
-                    |_canvas, story| {
-                        story.message.set("You work hard!".to_string());
-                        story.threshold.set(10_000);
-                        story.value.set(0);
-                        Ok(())
-                    },
-                )
+                .next("Set some sensible values to the Counter", |ctx| {
+                    ctx.story().message.set("You work hard!".to_string());
+                    ctx.story().threshold.set(10_000);
+                    ctx.story().value.set(0);
+                    Ok(())
+                })
                 .into()
         }]
     }
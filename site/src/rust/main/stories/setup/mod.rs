@@ -86,7 +86,6 @@ leptos = { version = "0.8", features = ["csr"] }
 forge = { version = "0.6", package = "leptos_forge", git="https://github.com/mskorkowski/leptos-forge.git" }
 ui_components = { version = "0.5", package = "leptos_forge_ui_components", git="https://github.com/mskorkowski/leptos-forge.git"  }
 utils_leptos = { version = "0.6", package = "leptos_forge_utils_leptos" }
-testing-library-dom = { version = "0.0.1", git="https://github.com/RustForWeb/testing-library.git", rev="05c93b5" }
 
 console_error_panic_hook = "0.1.7"
 console_log = "1.0.0"
@@ -114,8 +113,7 @@ Let's dissect the dependencies and their roles in this project:
 3. **`ui_components`**: This is a collection of components provided by `leptos_forge` so you
    can focus on your components instead of the control panel.
 4. **`utils_leptos`**: A small library with some utilities used in the `leptos_forge` project.
-5. **`testing-library-dom`**: This is the workhorse library for testing your components.
-6. **`console_error_panic_hook`**, **`console_log`**, and **`log`**: These are used to 
+5. **`console_error_panic_hook`**, **`console_log`**, and **`log`**: These are used to 
    set up error handling in your `leptos_forge`-based application.
 
 There are also three build dependencies:
@@ -127,11 +125,6 @@ There are also three build dependencies:
 3. **`leptos_forge_build_script`** - is the library intended to be used as part of
    Cargo build script. Provides a seamless tailwind integration.
 
-> [!NOTE]
->
-> The version of `testing-library-dom` which supports core features required for testing 
-> components has not been released to crates.io yet.
-
 #### How project structure will look like
 
 In the root of your project you should have the classic `cargo` file structure with `src` and 
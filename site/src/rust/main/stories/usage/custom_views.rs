@@ -42,29 +42,36 @@ pub enum RouteDef {
         ///   Current version of the Markdown parser doesn't allow to set this
         ///   value.
         /// 
+        /// - **tests** - wherever we should show the plays of the story under the canvas
+        /// 
         /// # Embedding in the section
         /// 
         /// Inside the Markdown returned from [Section::description] method
         /// you can add the `<Story />` tag. It has the following boolean attributes
         /// 
         /// - **controls**
-        /// 
+        /// - **tests**
         /// 
         /// The code below will enable all of the before mentioned attributes
         /// 
         /// ```markdown
         /// 
-        /// <Story of="path/to/the/substory" controls />
+        /// <Story of="path/to/the/substory" controls tests />
         /// 
         /// ```
         /// 
         /// 
-        embedded: fn(view: bool, controls: bool, description: bool) -> AnyView,
+        embedded: fn(view: bool, controls: bool, description: bool, tests: bool) -> AnyView,
         /// Wherever this route should be hidden from the menu/router but still
         /// provide to the story embedding resolution
         /// 
         /// hidden entry hides it's all children
         private: bool,
+        /// Returns the tests of the route
+        ///
+        /// Routes which don't have any tests, like sections, return `None`.
+        /// It's used by the runner to run the plays without the UI.
+        suite: fn() -> Option<Box<dyn TestSuite>>,
     },
     ...
 }
@@ -82,15 +89,17 @@ fn custom_entry() -> RouteDef {
 
         component: fn() { view!{ <MyCustomView /> }.into_any() },
         subroutes: vec![],
-        embedded: fn() { view!{ <MyCustomEmbeddedView /> }.into_any() },
+        embedded: fn(_, _, _, _) { view!{ <MyCustomEmbeddedView /> }.into_any() },
         private: false,
+        suite: || None,
     }
 }
 
 ```
 
 `component` function will be called when user navigates via menu entry and
-`embedded` will be called in place of story in the section.
+`embedded` will be called in place of story in the section. If your view has
+plays which should be run by `leptos_forge::runner`, return them from `suite`.
 
 Now just add as any other route in your application.
 
@@ -103,20 +103,21 @@ You can read more about this in the [Routing](http://localhost:8000/documentatio
 Syntax:
 
 ```
-<Story of="path/to/the/substory" controls/>
+<Story of="path/to/the/substory" controls tests />
 ```
 
 | Attribute | Value | Description |
 |:-----------|:---------|:----------------|
 | of | string | Path to the substory, relative to the section where you embed the story. It's not possible to "go up in sections tree" |
 | controls | bool | Boolean attribute. If present it will enable rendering of the embedded control panel |
+| tests | bool | Boolean attribute. If present the plays of the story are shown under the canvas, so they can be run in place |
 
-### Limitations of the `controls` attribute
+### Limitations of the boolean attributes
 
-Only supported syntax for the `controls` attribute is
+Only supported syntax for the `controls` and `tests` attributes is
 
 ```
-<Story ... controls />
+<Story ... controls tests />
 ```
 
 We don't support `controls=""` or other forms of the boolean attributes in html.
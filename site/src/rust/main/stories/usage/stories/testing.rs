@@ -31,7 +31,7 @@ The minimal example
 use leptos::prelude::*;
 use leptos_forge_ui_components::widgets::field::TextField;
 use leptos_forge::{Play, play, test_id };
-use testing_library_dom::{get_by_test_id, MatcherOptions};
+use leptos_forge::testing::{assert_text, get_by_test_id};
 
 #[derive(Debug, Clone, Copy)]
 pub struct MyStory{
@@ -72,27 +72,16 @@ impl Story for MyStory {
             play::<Self>("Check if the text can be updated").
                 next(
                     "Updata the text",
-                    |_canvas, story| {
-                        story.text.set(MESSAGE.to_string());
+                    |ctx| {
+                        ctx.story().text.set(MESSAGE.to_string());
                     }
                 ).
                 next(
                     "Check if text was updated",
-                    |canvas, _story| {
-                        let Ok(div) = get_by_test_id(canvas, "my-story-text", MatcherOptions::default()) {
-                            return Err("Div with message was not found on canvas?");
-                        }
+                    |ctx| {
+                        let div = ctx.get_by_test_id("my-story-text")?;
 
-                        let message = div.inner_text();
-                        if message.is_empty() {
-                            return Err("Message is not visible");
-                        }
-
-                        if message_as_str() != MESSAGE {
-                            return Err("Wrong message is shown!")
-                        }
-
-                        OK(())
+                        assert_text(&div, MESSAGE)
                     }
                 )
         }]
@@ -109,6 +98,615 @@ The interesting part start with the implementation of `MyStory::plays` method.
 
 To create a play we use function `leptos_forge::play`. 
 
+## Step context
+
+Every step receives the `StepContext`, which bundles everything the step needs
+
+| Method | Description |
+|:-------|:------------|
+| `canvas()` | Element in which the story is drawn |
+| `story()`, `story_mut()` | Story being played |
+| `get_by_test_id`, `get_by_role`, `get_by_label_text`, `get_by_text`, `get_element`, `query` | Queries inside of the canvas, the same as in the `leptos_forge::testing` module |
+| `log(message)` | Writes the message to the console, it's shown next to the step in the test viewer |
+| `elapsed()` | Time elapsed since the play has started |
+| `sleep(duration)` | Waits without blocking the browser, in the asynchronous steps |
+| `set(key, value)`, `get(key)`, `take(key)`, `contains(key)` | Values passed to the later steps of the same play |
+
+Steps and plays are recreated whenever they're needed, so they must not keep any
+data. When a step needs a value found by one of the previous steps, like an
+element or a counter, it stores it in the context
+
+```rust
+play::<TodoStory>("Added item is counted")
+    .next("Remember the number of items", |ctx| {
+        let count = query_all(ctx.canvas(), By::Role("listitem")).len();
+        ctx.set("count", count);
+        Ok(())
+    })
+    .next("Add the item", |ctx| {
+        type_text(&ctx.get_by_label_text("New item")?, "Milk")?;
+        click(&ctx.get_by_text("Add")?)
+    })
+    .next("Check the number of items", |ctx| {
+        let count: usize = ctx.get("count")?;
+        let items = query_all(ctx.canvas(), By::Role("listitem")).len();
+        assert_equal("Wrong number of items", count + 1, items)
+    })
+```
+
+Reading a value which wasn't stored, or reading it as a different type, fails
+the step. Stored values are cleared when the play starts again from its first step.
+
+## Waiting for the DOM
+
+Some DOM updates land only after the reactive system flushes. Instead of guessing
+how long you should `sleep`, you can use the `wait_for` function which checks the
+condition every `POLL_INTERVAL` until it passes or the timeout expires.
+
+```rust
+play("Data is loaded")
+    .next_async("Wait for the data", async |ctx| {
+        wait_for(
+            || {
+                if ctx.canvas().inner_text().is_empty() {
+                    Err("Data wasn't loaded".into())
+                } else {
+                    Ok(())
+                }
+            },
+            Duration::from_secs(1),
+        )
+        .await
+    })
+```
+
+You can also set the timeout of the whole step. Such step is retried until it
+passes or the timeout expires.
+
+```rust
+play("Counter is updated")
+    .next("Click the button", |ctx| { ... })
+    .next("Check the counter", |ctx| { ... })
+    .timeout(Duration::from_millis(500))
+```
+
+A step which doesn't pass in time fails with the "Timed out after N ms" message
+and the last failure of the step. Steps without the timeout fail if they don't
+complete within the `DEFAULT_STEP_TIMEOUT`, so the test viewer never hangs.
+
+If you implement the `Step` trait yourself, override the `Step::timeout` method.
+
+## Timings and performance budgets
+
+The test viewer shows how long every step and the whole play took. Next to the
+playback options it also shows how long it took from mounting the story to the
+first rendered frame of the canvas. The same timings are part of the exported
+results: every step and play in the JSON report has its `duration_ms`, and every
+play has its `render_ms`.
+
+A step can have a performance budget. The step which has passed, but took longer
+than its budget, fails with the budget and the real duration
+
+```rust
+play("Sorting is fast")
+    .next("Sort by the name", |ctx| {
+        click(&ctx.get_by_text("Name")?)
+    })
+    .budget(Duration::from_millis(50))
+```
+
+The duration of the step includes the re-render it has triggered, since the
+clock is stopped only when the browser is about to paint the next frame, so the
+budget catches the slow re-renders. The duration of the step with a timeout
+includes all of its retries. If you implement the `Step` trait
+yourself, override the `Step::budget` method.
+
+## Setup and teardown
+
+Plays can have a setup step run before the first step and a teardown step run
+after the last one. Use them to seed the signals, install mocks or remove the
+global event listeners.
+
+```rust
+play("Clicking the button")
+    .before("Seed the counter", |ctx| {
+        ctx.story().counter.set(10);
+        Ok(())
+    })
+    .next("Click the button", |ctx| { ... })
+    .after("Remove the listeners", |_ctx| { ... })
+```
+
+If you implement the `Play` trait yourself, override the `Play::before` and
+`Play::after` methods. Steps shared by every play of the story can be added with
+the `Story::before_each` and `Story::after_each` methods. They are run in the
+following order
+
+1. `Story::before_each`
+2. `Play::before`
+3. steps of the play
+4. `Play::after`
+5. `Story::after_each`
+
+Teardown steps are always run, even if the setup or any of the steps has failed,
+so the failure doesn't leak state into the next play. The failed setup fails the
+play, like any other failed step.
+
+## Resetting the story
+
+Every play should start from a clean state. The **Tests** tab has a **Reset**
+button which creates a new instance of the story using `IntoStory::into_story`,
+remounts the canvas and clears the results of all plays.
+
+When **Reset before each run** is checked (which is the default), the story is
+reset automatically whenever you start a play on a canvas which was already
+used by another run. Pressing **Play** or **Step** on a finished play always
+runs it again on a fresh story.
+
+## Playback controls
+
+While a play is running, the **Tests** tab lets you watch it step by step:
+
+- **Speed** sets the delay between the steps, from `0.25x` to `Instant`
+- **Pause** stops the play after the current step, **Resume** continues it and
+  **Step** runs only the next step
+- **Stop** ends the play; the teardown steps are still run and the play is
+  marked as `[STOPPED]`. It's also shown while you run the play step by step,
+  so you can end it before the last step without skipping its teardown
+
+Click the dot in front of any step to set a breakpoint. The play pauses right
+before that step, so you can inspect the canvas. Breakpoints and speed are kept
+when the story is reset.
+
+After every step, the element the step has found with a query or interacted
+with, like the clicked button or the field it typed into, is outlined in the
+canvas together with the description of the step. Queries returning many
+elements, like `query_all` and `get_all`, outline all of them. The outline moves
+with the canvas when it's scrolled and disappears after a moment, but when the
+step fails it stays in red, so you can see which element the failing step was
+looking at.
+
+## Run history and flaky plays
+
+The results of the last 20 runs of every play are kept in the `localStorage` of
+the browser, under the path of the story and the description of the play, so
+they survive reloading the page. The summary of the play shows them as a small
+bar chart, the oldest run first, together with the pass rate like
+`95% of 20 runs`. Plays which have both passed and failed are flagged as
+**flaky**. Stopped runs are not remembered.
+
+**Run 20 times** plays the play 20 times in a row, every time on a fresh story,
+which is the quickest way to find out how often a timing dependent play fails.
+Pick the `Instant` speed to make it faster, and press **Stop** to end the
+series early.
+
+## Recording a play
+
+Instead of writing the play by hand, you can record it. Press **Record** under
+the plays in the **Tests** tab and interact with the canvas. Every click, typed
+text, pressed key and selected option becomes a step of the play, and its code
+is shown below the buttons
+
+```rust
+play::<CounterStory>("Recorded play")
+    .next("Click the `+`", |ctx| {
+        click(&ctx.get_by_text("+")?)
+    })
+    .into()
+```
+
+Copy the code into the `plays` method of the story and add the steps checking
+the results. Elements are found with the most stable query available: the test
+id set with `test_id`, the label of the form control, the unique text and
+finally the role of the element. If none of them finds the element, the step
+contains a `compile_error!` asking to add a test id to it, so the play doesn't
+compile until the element can be found.
+
+Only the real user interactions are recorded, so running a play while recording
+doesn't add its steps to the recording.
+
+## Console output and panics
+
+While a step is running, the test viewer captures the messages logged with
+`console.log`, `console.info`, `console.warn` and `console.error`, including the
+ones logged from Rust. They are listed in the expandable **Console** entry under
+the step.
+
+Return the failure from the step instead of panicking. In the browser the panic
+aborts the WebAssembly module instead of unwinding it, so the panicking step
+never completes and the rest of the play, including its teardown steps, is not
+run. The test viewer tries to mark the step and the play as failed and to show
+the panic message as the failure, but it's only a best effort: the step can stay
+shown as running, and the programmatic test runner never returns its report.
+The application is left in a broken state, so reload the page before running
+the next play.
+
+## Linking to a play
+
+Story routes accept the query parameters which open the story in a given state
+
+| Parameter | Value | Description |
+|:----------|:------|:------------|
+| `tab` | `description`, `tests` or `accessibility` | Side panel tab to be shown. It's kept in sync with the selected tab |
+| `play` | index or description of the play | Play which is started as soon as the story is shown. It also opens the **Tests** tab |
+| `speed` | `0.25x`, `0.5x`, `1x`, `2x`, `instant`, `slowest`, `slow`, `normal` or `fast` | Speed of the started play |
+| `filter` | filter query, like `#keyboard -#slow` | Plays shown in the **Tests** tab, see **Tags and filters**. It also opens the **Tests** tab |
+
+For example `/components/button?play=Click%20the%20button&speed=fast` starts
+the play named `Click the button` at the double speed. It's useful for linking
+to a reproducing interaction in the bug reports.
+
+## DOM changes
+
+The test viewer records what every step changed in the canvas: added and
+removed elements, changed attributes and classes, and changed texts. The
+changes are listed in the expandable **DOM changes** entry under the step. When
+a step fails, check the changes of the previous step to see what the DOM looked
+like right before the failure.
+
+## Queries and assertions
+
+The `leptos_forge::testing` module contains helpers which work on the canvas
+passed to every step.
+
+Queries find the elements inside of the canvas. They are modeled after the
+[TestingLibrary](https://testing-library.com/docs/queries/about) queries.
+
+- `get` returns exactly one element or fails
+- `query` returns at most one element
+- `get_all` returns at least one element or fails
+- `query_all` returns all of the matching elements
+
+Elements can be found by
+
+- `By::TestId` - value of the `data-testid` attribute set with `test_id`
+- `By::Role` - explicit `role` attribute or the implicit role of the element, like `button` or `heading`
+- `By::Label` - text of the form control's `<label>`, `aria-label` or `aria-labelledby`
+- `By::Text` - text shown by the element
+
+There are also shortcuts like `get_by_test_id`, `get_by_role`, `get_by_label_text`
+and `get_by_text`. When the element can't be found, the failure contains the
+markup of the canvas, so you can see what went wrong.
+
+Assertions like `assert_text`, `assert_value`, `assert_attribute`,
+`assert_checked`, `assert_visible` and `assert_absent` return a `StepResult`
+with the expected and actual values, so they can be used with the `?` operator.
+
+## Accessibility
+
+The **Accessibility** tab next to the **Tests** audits the canvas for the most
+common accessibility problems. The rules are written in Rust and check
+
+- `image-alt` - images without the `alt` attribute
+- `form-label` - form controls without a label, like a `TextFieldLabel` whose `for_id` doesn't match the id of the field
+- `duplicate-id` - ids used by more than one element
+- `aria-role` - `role` attributes which are not valid ARIA roles
+- `button-name` - buttons without a text, `aria-label` or `title`
+- `color-contrast` - text with a contrast ratio below the WCAG AA level, computed from the styles of the text and its background
+
+Click the **Audit** button to run the audit. Every finding links to the
+offending element: clicking it scrolls the element into view and outlines it.
+Elements inside of `aria-hidden="true"` are skipped.
+
+The same checks can be run as a step of a play with `assert_accessible`, which
+fails with the list of the findings. Use `audit` to get the findings and check
+only some of them.
+
+```rust
+play("Counter is accessible")
+    .next("Audit the canvas", |ctx| assert_accessible(ctx.canvas()))
+```
+
+## User events
+
+User interactions are simulated with the functions from the `leptos_forge::testing`
+module. Every function dispatches the whole sequence of events the browser would
+dispatch, so the components listening to any of them will see them.
+
+- `click` and `dblclick` - `pointerdown`, `mousedown`, focus, `pointerup`, `mouseup` and `click`
+- `type_text` - `keydown`, `keypress`, `beforeinput`, `input` and `keyup` for every character, followed by `change`
+- `press_key` - `keydown`, `keypress` and `keyup`, `Backspace` and `Delete` also edit the value of the input
+- `hover` and `unhover` - pointer and mouse over, enter, move, out and leave events
+- `focus` and `blur`
+- `select_option` - selects the option of the `<select>` by value or text
+- `upload_file` - sets the file of the `<input type="file">`
+
+```rust
+play("Counter can be increased")
+    .next("Click the + button", |ctx| {
+        click(&ctx.get_by_text("+")?)
+    })
+```
+
+## Reporting failures
+
+Every step returns a `StepResult`, which is an alias for `Result<(), StepFailure>`.
+In the simplest case you can convert the message into the failure using
+`"message".into()`. If you would like to tell which value was wrong, you can
+add the expected and actual values and longer Markdown details to the failure.
+
+```rust
+use leptos_forge::StepFailure;
+
+if message != MESSAGE {
+    return Err(
+        StepFailure::mismatch("Wrong message is shown", MESSAGE, message)
+            .with_details("Message should be updated **immediately** after the signal changes")
+    );
+}
+```
+
+Test runner shows the failure with the expected and actual values below the
+failed step.
+
+## Asynchronous steps
+
+Sometimes a step can't check the result right away. The component might load
+data using a `Resource`, wait inside of the `Suspense` boundary or run an
+animation. For such cases you can add an asynchronous step using the
+`next_async` method. Test runner waits for the returned future to complete
+before it moves to the next step.
+
+```rust
+use std::time::Duration;
+
+use leptos_forge::{play, sleep};
+
+play::<MyStory>("Message is shown after the data is loaded")
+    .next_async(
+        "Wait for the data to be loaded",
+        async |ctx| {
+            sleep(Duration::from_millis(200)).await;
+
+            if ctx.canvas().inner_text().is_empty() {
+                return Err("Data wasn't loaded".into());
+            }
+
+            Ok(())
+        }
+    )
+```
+
+Asynchronous steps are async closures borrowing the `StepContext` of the play,
+so the changes of the story made with `story_mut` and the values stored with
+`set` are seen by the next steps.
+
+If you implement the `Step` trait yourself, override the `Step::run_async` method.
+
+## Reusable steps and sub-plays
+
+Steps are closures, so they can capture the values from their surroundings.
+Use it to build the steps and plays with the functions taking parameters,
+instead of copying the same steps between the plays.
+
+- `SimpleStep::new` creates a shared step, which is added to the play with `step`
+- `include` adds all of the steps of a sub-play. Setup and teardown steps of the
+  sub-play become ordinary steps of the play
+
+```rust
+fn increase(times: usize) -> SimplePlay<CounterStory> {
+    play::<CounterStory>("Increase the counter")
+        .next("Click the + button", move |ctx| {
+            for _ in 0..times {
+                click(&ctx.get_by_text("+")?)?;
+            }
+            Ok(())
+        })
+}
+
+fn check_value(expected: &'static str) -> SimpleStep<CounterStory> {
+    SimpleStep::new("Check the value", move |ctx| {
+        assert_text(&ctx.get_by_test_id("value")?, expected)
+    })
+}
+
+play::<CounterStory>("Counter is increased three times")
+    .include(increase(3))
+    .step(check_value("3"))
+```
+
+The `play!` macro is a shorter way of writing the same builder chain. Every
+line ends with a semicolon
+
+| Line | Adds |
+|:-----|:-----|
+| `before "description" => closure;` | Setup step of the play |
+| `step "description" => closure;` | Next step of the play |
+| `step async "description" => async closure;` | Next asynchronous step of the play |
+| `reuse step;` | Shared step |
+| `include play;` | All steps of the sub-play |
+| `timeout duration;` | Timeout of the last added step |
+| `budget duration;` | Performance budget of the last added step |
+| `tag "tag";` | Tag of the play |
+| `after "description" => closure;` | Teardown step of the play |
+
+```rust
+play! { CounterStory: "Counter is increased three times";
+    include increase(3);
+    step "Check the value" => |ctx| {
+        assert_text(&ctx.get_by_test_id("value")?, "3")
+    };
+    timeout Duration::from_millis(500);
+}
+```
+
+## Table driven plays
+
+When the same steps must be checked for many inputs, declare the play once and
+run it for every row of a table with the `table` function. Every row becomes
+its own play, so it shows up as a separate entry in the **Tests** tab with its
+own result. The description of the row is the description of the table followed
+by the value of the row, like `Invalid email is rejected: "a@"`.
+
+```rust
+fn plays(&self) -> Vec<Box<dyn Play<Story = Self>>> {
+    let mut plays = table::<Self, _>("Invalid email is rejected", ["", "a@", "@b.c"], |play, email| {
+        play.next("Type the email", move |ctx| {
+            type_text(&ctx.get_by_label_text("Email")?, email)
+        })
+        .next("Check the error", |ctx| {
+            assert_visible(&ctx.get_by_test_id("email-error")?)
+        })
+    });
+
+    plays.push(play::<Self>("Valid email is accepted").next(...).into());
+    plays
+}
+```
+
+The value of the row is moved into the `steps` function, so the steps can
+capture it. Rows can be of any type implementing `Debug`, like tuples with the
+input and the expected output.
+
+## Property based plays
+
+With the `proptest` feature of `leptos_forge` enabled, you can check a property
+of the story for many generated inputs. The inputs are sampled from any
+`proptest::strategy::Strategy`, including the ones from
+`leptos_forge_utils::proptest`, like `random_uuid` and `vec_of_vec`.
+
+```rust
+use leptos_forge::property;
+use leptos_forge::testing::*;
+
+property::<CounterStory, _>("Message is shown above the threshold", 0..20_000u32)
+    .apply(|story, value| story.value.set(*value))
+    .check("Check the message", |ctx, value| {
+        let message = ctx.get_by_test_id("message")?;
+
+        if *value > ctx.story().threshold.get_untracked() {
+            assert_text(&message, &ctx.story().message.get_untracked())
+        } else {
+            assert_text(&message, "")
+        }
+    })
+    .cases(64)
+    .into()
+```
+
+For every input the function passed to `apply` updates the story, and then all
+of the checks are run. The whole play is shown as a single step in the test
+viewer. When a check fails, the input is shrunk to the smallest one which still
+fails, and the failure shows it together with the failed check and the seed of
+the generator. Pass the seed to `seed` to replay the same inputs while you fix
+the story.
+
+The story is not reset between the inputs, every case starts with the story left
+by the previous one. Set every signal which the checks read in `apply`, not only
+the ones which depend on the input, otherwise a case can pass or fail because of
+the input of the case before it.
+
+| Method | Description | Default |
+|:-------|:------------|:--------|
+| `apply(Fn(&mut S, &T))` | Applies the generated input to the story | Input is not applied |
+| `check(name, Fn(&mut StepContext<S>, &T) -> StepResult)` | Adds the check run for every input | |
+| `cases(u32)` | Number of the generated inputs | 32 |
+| `max_shrink_iters(u32)` | Maximum number of the shrinking steps | 256 |
+| `seed([u8; 32])` | Seed of the generator, which makes every run check the same inputs | Random seed |
+
+## Tags and filters
+
+Plays can carry tags, like `smoke`, `keyboard` or `slow`, which are added with
+the `tag` method of the play. Tags are shown next to the name of the play in the
+**Tests** tab.
+
+```rust
+play::<FormStory>("Form can be submitted with the keyboard")
+    .tag("keyboard")
+    .tag("smoke")
+    .next("Press Enter", |ctx| press_key(&ctx.get_by_label_text("Name")?, "Enter"))
+```
+
+If you implement the `Play` trait yourself, override the `Play::tags` method.
+
+The **Tests** tab, the **Run all tests** dashboard and the runner select the
+plays with the filter query. The query is made of words separated by spaces
+
+| Word | Description |
+|:-----|:------------|
+| `#tag` | Play must have the tag |
+| `-#tag` | Play must not have the tag |
+| any other word | Text which must be a part of the description of the play, ignoring the case |
+
+For example `#keyboard` selects just the keyboard plays before a release, and
+`-#slow` skips the slow ones during the local development. Plays hidden by the
+filter in the **Tests** tab keep their results, they show up again when the
+filter changes.
+
+## Running plays on the CI
+
+Plays can be run without the UI using the `leptos_forge::runner` module. The
+runner walks the whole routes tree, mounts every story in a fresh canvas and
+runs all of its plays one after another. The resulting report can be exported
+as JSON or JUnit XML.
+
+```rust
+use leptos_forge::runner::{run, RunOptions};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn all_plays() {
+    // the same routes you pass to the `App` component
+    let routes = routes();
+
+    let report = run(&routes, &RunOptions::default()).await;
+
+    web_sys::console::log_1(&report.to_junit().into());
+
+    assert!(report.is_success(), "{}", report.summary());
+}
+```
+
+To run only some of the plays, set the filter of the run options. Plays which
+don't match it are reported as skipped. The report still lists every story, the
+story without matching plays has all of its plays skipped.
+
+```rust
+let options = RunOptions {
+    filter: PlayFilter::parse("#smoke -#slow"),
+    ..RunOptions::default()
+};
+```
+
+## Running all plays in the browser
+
+Every `leptos_forge` application has a **Run all tests** entry at the bottom of
+the menu. It opens the dashboard available under `/leptos_forge/tests` which
+runs every play of every story one after another and shows the number of
+passed, failed and skipped plays for each story. Failing plays link back to
+their story, which starts the failed play, and show the failed step with its
+message. The **Filter** next to the **Run** button selects the plays which are
+run, using the same query as the **Tests** tab.
+
+After the run the menu entry shows a badge with the number of failed plays.
+
+## Coverage
+
+The dashboard also lists every story with the number of its plays and steps,
+and flags the stories which have no plays or which still show the default
+**New Story** cheat sheet as their description.
+
+The same data is available from `leptos_forge::runner::coverage`, so you can
+enforce it on the CI
+
+```rust
+use leptos_forge::runner::coverage;
+
+#[wasm_bindgen_test]
+fn every_story_is_covered() {
+    let report = coverage(&routes());
+
+    let uncovered = report
+        .uncovered()
+        .map(|story| format!("{}: {}", story.path, story.issues().join(", ")))
+        .collect::<Vec<_>>();
+
+    assert!(uncovered.is_empty(), "{}", uncovered.join("\n"));
+}
+```
+
 "############;
 
 /// Section about [Play][forge::Play]